@@ -0,0 +1,23 @@
+#!/usr/bin/env python3
+# An arena bot: always guesses the middle of what's still possible.
+# Try it with: guessing_game arena "python3 scripts/binary_search_bot.py"
+import json
+import sys
+
+low = high = guess = None
+for line in sys.stdin:
+    message = json.loads(line)
+    kind = message["type"]
+    if kind == "started":
+        low, high = message["low"], message["high"]
+    elif kind == "feedback":
+        if message["result"] == "too_small":
+            low = message["guess"] + 1
+        elif message["result"] == "too_big":
+            high = message["guess"] - 1
+        else:
+            continue
+    else:
+        continue
+    guess = (low + high) // 2
+    print(json.dumps({"type": "guess", "value": guess}), flush=True)
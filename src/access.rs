@@ -0,0 +1,27 @@
+// Accessible mode, for screen readers and terminals without color: every
+// answer is one full sentence, nothing is drawn with escape codes, and no
+// color is used. Color is also off whenever NO_COLOR is set
+// (https://no-color.org).
+use std::env;
+use std::sync::OnceLock;
+
+static ACCESSIBLE: OnceLock<bool> = OnceLock::new();
+
+pub fn init(enabled: bool) {
+    let _ = ACCESSIBLE.set(enabled);
+}
+
+pub fn enabled() -> bool {
+    ACCESSIBLE.get().copied().unwrap_or(false)
+}
+
+// Whether anything may be colored. Colors only ever repeat what the words
+// already say.
+pub fn color() -> bool {
+    !enabled() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+// Whether the screen may be redrawn in place, e.g. a ticking clock.
+pub fn redraw() -> bool {
+    !enabled()
+}
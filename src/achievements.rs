@@ -0,0 +1,136 @@
+use crate::daily::format_date;
+use crate::game::Difficulty;
+use crate::history::{self, GameRecord};
+use crate::i18n;
+use crate::profile::{Profile, Unlocked};
+
+pub struct Achievement {
+    pub id: &'static str,
+    // message keys; name() and description() are what the player sees
+    name: &'static str,
+    description: &'static str,
+    // Given the game that just finished and the whole history (that game
+    // last), has the player earned it?
+    pub earned: fn(&GameRecord, &[GameRecord]) -> bool,
+}
+
+// Ids are stored in profiles, so never change or reuse one.
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first-win",
+        name: "achievements.first_win.name",
+        description: "achievements.first_win.description",
+        earned: |game, _| game.won,
+    },
+    Achievement {
+        id: "one-guess",
+        name: "achievements.one_guess.name",
+        description: "achievements.one_guess.description",
+        earned: |game, _| game.won && game.attempts == 1,
+    },
+    Achievement {
+        id: "binary-search",
+        name: "achievements.binary_search.name",
+        description: "achievements.binary_search.description",
+        earned: |game, _| game.won && game.binary_search && game.attempts > 1,
+    },
+    Achievement {
+        id: "streak-10",
+        name: "achievements.streak.name",
+        description: "achievements.streak.description",
+        earned: |_, history| {
+            history.len() >= 10 && history[history.len() - 10..].iter().all(|g| g.won)
+        },
+    },
+    Achievement {
+        id: "nightmare",
+        name: "achievements.nightmare.name",
+        description: "achievements.nightmare.description",
+        earned: |game, _| game.won && game.difficulty == Some(Difficulty::Nightmare),
+    },
+    Achievement {
+        id: "daily-7",
+        name: "achievements.daily.name",
+        description: "achievements.daily.description",
+        earned: |_, history| {
+            history
+                .iter()
+                .filter(|g| g.mode == "daily" && g.won)
+                .count()
+                >= 7
+        },
+    },
+    Achievement {
+        id: "veteran",
+        name: "achievements.veteran.name",
+        description: "achievements.veteran.description",
+        earned: |_, history| history.len() >= 100,
+    },
+];
+
+impl Achievement {
+    pub fn name(&self) -> String {
+        i18n::text(self.name, &[])
+    }
+
+    pub fn description(&self) -> String {
+        i18n::text(self.description, &[])
+    }
+}
+
+// Unlocks everything the latest game in the history earned, once per
+// profile, and returns what was new.
+pub fn unlock(profile: &mut Profile) -> Vec<&'static Achievement> {
+    let Some(game) = profile.history.last() else {
+        return Vec::new();
+    };
+    let new: Vec<&'static Achievement> = ACHIEVEMENTS
+        .iter()
+        .filter(|a| !profile.achievements.iter().any(|u| u.id == a.id))
+        .filter(|a| (a.earned)(game, &profile.history))
+        .collect();
+    let now = history::now();
+    for achievement in &new {
+        profile.achievements.push(Unlocked {
+            id: achievement.id.to_string(),
+            unlocked_at: now,
+        });
+    }
+    new
+}
+
+pub fn list(player: &str) -> Result<(), String> {
+    let profile = Profile::load(player)?;
+    let unlocked = profile.achievements.len();
+    let title = i18n::text(
+        "achievements.title",
+        &[
+            ("player", &profile.name),
+            ("unlocked", &unlocked),
+            ("total", &ACHIEVEMENTS.len()),
+        ],
+    );
+    println!("{}", title);
+    for achievement in ACHIEVEMENTS {
+        let when = profile
+            .achievements
+            .iter()
+            .find(|u| u.id == achievement.id)
+            .map(|u| format_date((u.unlocked_at / 86_400) as i64));
+        match when {
+            Some(date) => println!(
+                "  [x] {:<20} {}  ({})",
+                achievement.name(),
+                date,
+                achievement.description()
+            ),
+            None => println!(
+                "  [ ] {:<20} {:<10}  ({})",
+                achievement.name(),
+                "",
+                achievement.description()
+            ),
+        }
+    }
+    Ok(())
+}
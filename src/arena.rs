@@ -0,0 +1,286 @@
+// Bots against the same secrets. Each bot is a program speaking the client
+// side of the protocol in PROTOCOL.md, except that the arena starts the
+// games: the bot only ever sends guesses.
+use crate::game::Game;
+use crate::i18n;
+use crate::protocol::{self, Reply, Request};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+const LOW: i64 = 1;
+const HIGH: i64 = 100;
+// Binary search needs 7; anything past this is going nowhere.
+const MAX_GUESSES: usize = 50;
+
+pub struct Settings {
+    games: usize,
+    seed: u64,
+    timeout: Duration,
+    bots: Vec<String>,
+}
+
+impl Settings {
+    // arena [--games N] [--timeout MS] <bot command>..., with the global
+    // --seed. A bot command with arguments goes in quotes: "python3 bot.py".
+    pub fn from_args(args: &[String], seed: Option<u64>) -> Result<Settings, String> {
+        let mut settings = Settings {
+            games: 10,
+            seed: seed.unwrap_or_else(rand::random),
+            timeout: Duration::from_millis(1000),
+            bots: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .ok_or_else(|| format!("{} needs a number", name))
+            };
+            match arg.as_str() {
+                "--games" => settings.games = value("--games")?.max(1) as usize,
+                "--timeout" => settings.timeout = Duration::from_millis(value("--timeout")?),
+                _ => settings.bots.push(arg.clone()),
+            }
+        }
+        if settings.bots.is_empty() {
+            return Err(String::from(
+                "Usage: arena [--games N] [--timeout MS] <bot command>...",
+            ));
+        }
+        Ok(settings)
+    }
+}
+
+enum Failure {
+    Timeout,
+    Illegal(String),
+    Crashed,
+    OutOfGuesses,
+}
+
+struct Bot {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl Bot {
+    fn spawn(command: &str) -> Result<Bot, String> {
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| String::from("Empty bot command"))?;
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| i18n::text("arena.cant_start", &[("bot", &command), ("error", &e)]))?;
+
+        // read on a thread so a silent bot can be timed out
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    return;
+                };
+                if sender.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let mut bot = Bot {
+            child,
+            stdin,
+            lines,
+        };
+        bot.send(&Reply::Ready {
+            version: protocol::VERSION,
+        })
+        .map_err(|_| i18n::text("arena.quit_at_once", &[("bot", &command)]))?;
+        Ok(bot)
+    }
+
+    fn send(&mut self, reply: &Reply) -> Result<(), Failure> {
+        writeln!(self.stdin, "{}", protocol::encode(reply)).map_err(|_| Failure::Crashed)
+    }
+
+    fn replies_since(&mut self, game: &Game<i64>, seen: usize) -> Result<(), Failure> {
+        for event in &game.events()[seen..] {
+            for reply in protocol::replies(game, event) {
+                self.send(&reply)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn next_guess(&self, game: &Game<i64>, timeout: Duration) -> Result<i64, Failure> {
+        let line = match self.lines.recv_timeout(timeout) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Timeout) => return Err(Failure::Timeout),
+            Err(RecvTimeoutError::Disconnected) => return Err(Failure::Crashed),
+        };
+        match serde_json::from_str(&line) {
+            Ok(Request::Guess { value }) if value < game.low || value > game.high => {
+                Err(Failure::Illegal(i18n::text(
+                    "arena.outside",
+                    &[("value", &value), ("low", &game.low), ("high", &game.high)],
+                )))
+            }
+            Ok(Request::Guess { value }) => Ok(value),
+            Ok(_) => Err(Failure::Illegal(i18n::text("arena.only_guesses", &[]))),
+            Err(_) => Err(Failure::Illegal(i18n::text(
+                "arena.not_a_request",
+                &[("line", &line)],
+            ))),
+        }
+    }
+
+    // The number of guesses it took.
+    fn play(&mut self, seed: u64, timeout: Duration) -> Result<usize, Failure> {
+        let mut game = Game::seeded(LOW, HIGH, 0, seed).with_max_attempts(Some(MAX_GUESSES));
+        self.replies_since(&game, 0)?;
+        while !game.is_finished() {
+            let guess = self.next_guess(&game, timeout)?;
+            let seen = game.events().len();
+            game.guess(guess);
+            self.replies_since(&game, seen)?;
+        }
+        if game.won() {
+            Ok(game.attempts())
+        } else {
+            Err(Failure::OutOfGuesses)
+        }
+    }
+}
+
+impl Drop for Bot {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[derive(Default)]
+struct Tally {
+    won: usize,
+    guesses: usize,
+    worst: usize,
+    timeouts: usize,
+    illegal: usize,
+    crashed: usize,
+    out_of_guesses: usize,
+}
+
+impl Tally {
+    fn failures(&self) -> usize {
+        self.timeouts + self.illegal + self.crashed + self.out_of_guesses
+    }
+
+    fn average(&self) -> Option<f64> {
+        (self.won > 0).then(|| self.guesses as f64 / self.won as f64)
+    }
+}
+
+fn run_bot(command: &str, settings: &Settings) -> Tally {
+    let mut tally = Tally::default();
+    let mut bot = Bot::spawn(command);
+    for game in 0..settings.games {
+        let seed = settings.seed.wrapping_add(game as u64);
+        let result = match bot.as_mut() {
+            Ok(bot) => bot.play(seed, settings.timeout),
+            Err(_) => Err(Failure::Crashed),
+        };
+        match result {
+            Ok(guesses) => {
+                tally.won += 1;
+                tally.guesses += guesses;
+                tally.worst = tally.worst.max(guesses);
+                continue;
+            }
+            Err(Failure::Timeout) => {
+                tally.timeouts += 1;
+                let line = i18n::text("arena.timeout", &[("bot", &command), ("game", &(game + 1))]);
+                println!("  {}", line);
+            }
+            Err(Failure::Illegal(why)) => {
+                tally.illegal += 1;
+                let line = i18n::text(
+                    "arena.illegal",
+                    &[("bot", &command), ("game", &(game + 1)), ("why", &why)],
+                );
+                println!("  {}", line);
+            }
+            Err(Failure::Crashed) => {
+                tally.crashed += 1;
+                let line = match &bot {
+                    Ok(_) => i18n::text("arena.quit", &[("bot", &command), ("game", &(game + 1))]),
+                    Err(message) => i18n::text(
+                        "arena.failed",
+                        &[("bot", &command), ("game", &(game + 1)), ("error", message)],
+                    ),
+                };
+                println!("  {}", line);
+            }
+            Err(Failure::OutOfGuesses) => tally.out_of_guesses += 1,
+        }
+        // a bot that failed may be stuck or out of step, so start it afresh
+        bot = Bot::spawn(command);
+    }
+    tally
+}
+
+pub fn run(settings: Settings) {
+    let games = i18n::plural("count.games", settings.games as u64, &[]);
+    let summary = i18n::text(
+        "arena.summary",
+        &[
+            ("games", &games),
+            ("low", &LOW),
+            ("high", &HIGH),
+            ("seed", &settings.seed),
+            ("millis", &settings.timeout.as_millis()),
+        ],
+    );
+    println!("{}", summary);
+
+    let mut results: Vec<(&String, Tally)> = settings
+        .bots
+        .iter()
+        .map(|command| (command, run_bot(command, &settings)))
+        .collect();
+    // most wins first, then fewest guesses per win
+    results.sort_by(|(_, a), (_, b)| {
+        b.won.cmp(&a.won).then(
+            a.average()
+                .unwrap_or(f64::MAX)
+                .total_cmp(&b.average().unwrap_or(f64::MAX)),
+        )
+    });
+
+    println!();
+    println!("{}", i18n::text("arena.table", &[]));
+    for (rank, (command, tally)) in results.iter().enumerate() {
+        let average = tally
+            .average()
+            .map_or(String::from("-"), |a| format!("{:.2}", a));
+        println!(
+            "{:>4}  {:<20}  {:>3}  {:>4}  {:>5}  {:>6}  {:>7}  {:>7}  {:>7}",
+            rank + 1,
+            command,
+            tally.won,
+            average,
+            tally.worst,
+            tally.failures(),
+            tally.timeouts,
+            tally.illegal,
+            tally.crashed
+        );
+    }
+}
@@ -0,0 +1,244 @@
+// The classic game and its range and decimal variants: one secret, "Too
+// small" or "Too big!" until it's found. These are the games that are saved
+// as they go and can be resumed.
+use crate::cli::Error;
+use crate::game::{self, Decimal, Difficulty, Game, Number};
+use crate::history::GameRecord;
+use crate::i18n;
+use crate::mode::{self, GameMode, Score, Setup};
+use crate::replay::{Input, Replay};
+use crate::save::{self, SavedGame};
+use std::time::Instant;
+
+pub struct Classic<T> {
+    game: Game<T>,
+    // everything needed to pick the game up again
+    saved: SavedGame,
+    // time played in earlier sittings
+    before: u64,
+    start: Instant,
+    // false for games from --input-file: nothing is saved or recorded
+    recorded: bool,
+    resumed: bool,
+}
+
+impl<T: Number> Classic<T> {
+    pub fn new(
+        setup: &Setup,
+        difficulty: Option<Difficulty>,
+        mode: &str,
+        intro: Vec<String>,
+        game: Game<T>,
+    ) -> Classic<T> {
+        let saved = SavedGame {
+            player: setup.player.clone(),
+            difficulty,
+            millis: 0,
+            replay: Replay::start(mode, &intro, &game),
+        };
+        Classic {
+            game,
+            saved,
+            before: 0,
+            start: Instant::now(),
+            recorded: setup.recorded(),
+            resumed: false,
+        }
+    }
+
+    // A saved game, picked up where it was left.
+    pub fn resumed(saved: SavedGame, game: Game<T>) -> Classic<T> {
+        Classic {
+            game,
+            before: saved.millis,
+            saved,
+            start: Instant::now(),
+            recorded: true,
+            resumed: true,
+        }
+    }
+}
+
+impl<T> Classic<T> {
+    fn millis(&self) -> u64 {
+        self.before + self.start.elapsed().as_millis() as u64
+    }
+}
+
+impl<T: Number> GameMode for Classic<T> {
+    fn name(&self) -> String {
+        self.saved.replay.mode.clone()
+    }
+
+    fn intro(&self) -> Vec<String> {
+        let mut intro = self.saved.replay.intro.clone();
+        if self.resumed {
+            let seconds = format!("{:.1}", self.before as f64 / 1000.0);
+            intro.push(i18n::text("resume.welcome", &[("seconds", &seconds)]));
+            for &(guess, result) in self.game.history() {
+                intro.push(format!("  {}: {}", guess, game::hint(result)));
+            }
+        }
+        intro
+    }
+
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String> {
+        if self.recorded {
+            let at_ms = self.millis();
+            self.saved.replay.inputs.push(Input {
+                at_ms,
+                line: input.to_string(),
+            });
+        }
+        game::answer(&mut self.game, input)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.game.is_finished()
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if !self.game.is_finished() {
+            lines.extend(game::give_up(&mut self.game));
+        }
+        if self.recorded {
+            save::remove(&self.saved.player);
+            lines.extend(self.saved.replay.finish(&self.game));
+        }
+        lines
+    }
+
+    fn score(&self) -> Score {
+        Score {
+            won: self.game.won(),
+            attempts: self.game.attempts(),
+            points: None,
+        }
+    }
+
+    fn record(&self, millis: u64) -> Option<GameRecord> {
+        let won = self.game.won();
+        (self.game.attempts() > 0).then(|| GameRecord {
+            difficulty: self.saved.difficulty,
+            ..GameRecord::from_game(&self.name(), &self.game, won, self.before + millis)
+        })
+    }
+
+    fn saves(&self) -> bool {
+        self.recorded
+    }
+
+    // Even before the first guess: the seed alone brings the secret back.
+    fn save(&mut self) -> Result<bool, String> {
+        if !self.recorded {
+            return Ok(false);
+        }
+        self.saved.millis = self.millis();
+        save::store(&self.saved)?;
+        Ok(true)
+    }
+}
+
+// easy, normal, hard or nightmare, from 1 up to the difficulty's high end.
+pub fn difficulty(
+    difficulty: Difficulty,
+    args: &[String],
+    setup: &Setup,
+) -> Result<Box<dyn GameMode>, Error> {
+    mode::at_most(difficulty.name(), args, 0)?;
+    let mut intro = vec![i18n::text(
+        "intro.classic",
+        &[
+            ("high", &difficulty.high()),
+            ("difficulty", &difficulty.name()),
+        ],
+    )];
+    if let Some(max) = difficulty.max_attempts() {
+        intro.push(i18n::plural("intro.attempts", max as u64, &[]));
+    }
+    let game = difficulty.game(setup.seed);
+    Ok(Box::new(Classic::new(
+        setup,
+        Some(difficulty),
+        "classic",
+        intro,
+        game,
+    )))
+}
+
+// range <low> <high>: i64 when both ends fit, otherwise u128.
+pub fn range(args: &[String], setup: &Setup) -> Result<Box<dyn GameMode>, Error> {
+    let [low, high] = args else {
+        return Err(Error::Usage(String::from("Usage: play range <low> <high>")));
+    };
+    if let (Ok(low), Ok(high)) = (low.parse::<i64>(), high.parse::<i64>()) {
+        return start("range", Vec::new(), low, high, 0, setup);
+    }
+    match (low.parse::<u128>(), high.parse::<u128>()) {
+        (Ok(low), Ok(high)) => start("range", Vec::new(), low, high, 0, setup),
+        _ => Err(Error::Usage(String::from(
+            "Ranges must fit in i64, or be non-negative and fit in u128",
+        ))),
+    }
+}
+
+// decimal <low> <high> [places] [tolerance]
+pub fn decimal(args: &[String], setup: &Setup) -> Result<Box<dyn GameMode>, Error> {
+    if args.len() < 2 || args.len() > 4 {
+        return Err(Error::Usage(String::from(
+            "Usage: play decimal <low> <high> [places] [tolerance]",
+        )));
+    }
+    let places: u32 = match args.get(2) {
+        Some(p) => p
+            .parse()
+            .map_err(|_| Error::Usage(format!("'{}' is not a number of places", p)))?,
+        None => 2,
+    };
+    if places > Decimal::MAX_PLACES {
+        return Err(Error::Usage(format!(
+            "At most {} places",
+            Decimal::MAX_PLACES
+        )));
+    }
+    let parse = |input: &str| {
+        Decimal::parse(input)
+            .and_then(|d| {
+                d.rescale(places)
+                    .ok_or_else(|| format!("{} is too big for {} places", input, places))
+            })
+            .map_err(Error::Usage)
+    };
+    let low = parse(&args[0])?;
+    let high = parse(&args[1])?;
+    let tolerance = parse(args.get(3).map_or("0.1", String::as_str))?;
+    if tolerance < parse("0")? {
+        return Err(Error::Usage(String::from(
+            "The tolerance can't be negative",
+        )));
+    }
+    let intro = vec![i18n::text("intro.tolerance", &[("tolerance", &tolerance)])];
+    start("decimal", intro, low, high, tolerance, setup)
+}
+
+fn start<T: Number + 'static>(
+    mode: &str,
+    mut intro: Vec<String>,
+    low: T,
+    high: T,
+    tolerance: T,
+    setup: &Setup,
+) -> Result<Box<dyn GameMode>, Error> {
+    if low >= high {
+        return Err(Error::Usage(String::from(
+            "The low end must be below the high end",
+        )));
+    }
+    intro.insert(
+        0,
+        i18n::text("intro.range", &[("low", &low), ("high", &high)]),
+    );
+    let game = Game::seeded(low, high, tolerance, setup.seed);
+    Ok(Box::new(Classic::new(setup, None, mode, intro, game)))
+}
@@ -0,0 +1,347 @@
+// The command line: global options, the commands and their help.
+use crate::config;
+use crate::mode;
+use crate::profile;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+// What went wrong, and so the exit code: 2 for a mistake on the command line,
+// 1 for anything that failed while running.
+pub enum Error {
+    Usage(String),
+    Failed(String),
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Error {
+        Error::Failed(message)
+    }
+}
+
+// Options that apply to every command, wherever they are on the line.
+pub struct Options {
+    pub player: String,
+    // the same seed gives the same secrets; only for commands that list
+    // --seed among their options
+    pub seed: Option<u64>,
+    pub config: PathBuf,
+    pub accessible: bool,
+}
+
+pub const GLOBAL_OPTIONS: &[(&str, &str)] = &[
+    (
+        "--player NAME",
+        "play or look things up as NAME (default: your login)",
+    ),
+    (
+        "--config FILE",
+        "use FILE instead of config.yaml in the data directory",
+    ),
+    ("--quiet", "leave out introductions and notices"),
+    (
+        "--accessible",
+        "full sentences, no color (also `accessible: true`)",
+    ),
+    ("--help", "show help for the command"),
+];
+
+// Taken wherever it is on the line like the global options, but only the
+// commands that list it do anything with it.
+const SEED_OPTION: (&str, &str) = ("--seed N", "the same secrets every time");
+
+pub struct Command {
+    pub name: &'static str,
+    pub usage: &'static [&'static str],
+    pub about: &'static str,
+    // options of its own, on top of the global ones
+    pub options: &'static [(&'static str, &'static str)],
+}
+
+pub const COMMANDS: &[Command] = &[
+    Command {
+        name: "play",
+        usage: &["play [mode] [arguments]"],
+        about: "Play a game; a classic game on normal if no mode is given. \
+                This is also what runs with no command at all.",
+        options: &[
+            (
+                "--input-file FILE",
+                "take guesses from FILE, one per line; nothing is saved or recorded",
+            ),
+            (
+                "--seed N",
+                "the same secrets every time; nothing is saved or recorded",
+            ),
+        ],
+    },
+    Command {
+        name: "practice",
+        usage: &["practice [high]"],
+        about: "Play with undo and hints. Practice games aren't recorded.",
+        options: &[],
+    },
+    Command {
+        name: "daily",
+        usage: &["daily"],
+        about: "Today's challenge: everyone gets the same number, once a day.",
+        options: &[],
+    },
+    Command {
+        name: "resume",
+        usage: &["resume"],
+        about: "Carry on with the classic, range or decimal game you left with \
+                Ctrl-C or at the end of input.",
+        options: &[],
+    },
+    Command {
+        name: "stats",
+        usage: &["stats [--mode MODE] [--from YYYY-MM-DD] [--to YYYY-MM-DD]"],
+        about: "How the player has done: win rate, attempts by difficulty, best \
+                and worst games, streaks, efficiency by month and a histogram.",
+        options: &[
+            (
+                "--mode MODE",
+                "only count games of MODE, e.g. classic or daily",
+            ),
+            ("--from DATE", "only games finished on or after DATE"),
+            ("--to DATE", "only games finished on or before DATE"),
+        ],
+    },
+    Command {
+        name: "leaderboard",
+        usage: &["leaderboard [--mode MODE] [--top N]"],
+        about: "Every player ranked by games won, then by fewest attempts.",
+        options: &[
+            (
+                "--mode MODE",
+                "only count games of MODE, e.g. classic or daily",
+            ),
+            ("--top N", "show the first N players (default 10)"),
+        ],
+    },
+    Command {
+        name: "replay",
+        usage: &["replay", "replay <file> [speed]"],
+        about: "List recent replays, or play one back. Speed 2 is twice as \
+                fast, 0 as fast as possible.",
+        options: &[],
+    },
+    Command {
+        name: "simulate",
+        usage: &["simulate [--games N] [--high N] [--strategy binary|random|linear]"],
+        about: "Let a strategy play many games and see how it does.",
+        options: &[
+            ("--games N", "how many games (default 1000)"),
+            ("--high N", "guess between 1 and N (default 100)"),
+            (
+                "--strategy NAME",
+                "binary, random or linear (default binary)",
+            ),
+            SEED_OPTION,
+        ],
+    },
+    Command {
+        name: "profile",
+        usage: &["profile", "profile list"],
+        about: "Show the player's profile, or list every player.",
+        options: &[],
+    },
+    Command {
+        name: "achievements",
+        usage: &["achievements"],
+        about: "The achievements the player has unlocked, and those still to get.",
+        options: &[],
+    },
+    Command {
+        name: "tournament",
+        usage: &[
+            "tournament new [--force] <single|roundrobin> <player> <player>...",
+            "tournament play",
+            "tournament show",
+        ],
+        about: "A hot-seat tournament: start one, play the next match or show \
+                the standings.",
+        options: &[("--force", "start a new tournament over one that isn't over")],
+    },
+    #[cfg(feature = "json")]
+    Command {
+        name: "arena",
+        usage: &["arena [--games N] [--timeout MS] <bot command>..."],
+        about: "Rank bots that speak the protocol against the same secrets. \
+                See PROTOCOL.md.",
+        options: &[
+            ("--games N", "games per bot (default 10)"),
+            ("--timeout MS", "time allowed per guess (default 1000)"),
+            SEED_OPTION,
+        ],
+    },
+    #[cfg(feature = "json")]
+    Command {
+        name: "protocol",
+        usage: &["protocol"],
+        about: "Play over stdin and stdout with one JSON object per line. \
+                See PROTOCOL.md.",
+        options: &[],
+    },
+    Command {
+        name: "config",
+        usage: &[
+            "config",
+            "config path",
+            "config set <key> <value>",
+            "config unset <key>",
+        ],
+        about: "Show or change the config file. Keys: language, accessible, theme. \
+                Changing it rewrites the file without its comments.",
+        options: &[],
+    },
+    Command {
+        name: "help",
+        usage: &["help [command]"],
+        about: "Show this help, or the help for one command.",
+        options: &[],
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|command| command.name == name)
+}
+
+// Removes `name` from the arguments, wherever it is, and says whether it
+// was there.
+pub fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+    let before = args.len();
+    args.retain(|a| a != name);
+    args.len() != before
+}
+
+// Removes `name value` from the arguments, wherever it is, and returns the
+// value.
+pub fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, Error> {
+    let Some(at) = args.iter().position(|a| a == name) else {
+        return Ok(None);
+    };
+    args.remove(at);
+    if at == args.len() {
+        return Err(Error::Usage(format!("{} needs a value", name)));
+    }
+    Ok(Some(args.remove(at)))
+}
+
+// `take_option` for a number.
+pub fn take_number(args: &mut Vec<String>, name: &str) -> Result<Option<u64>, Error> {
+    match take_option(args, name)? {
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| Error::Usage(format!("{} needs a number, not '{}'", name, value))),
+        None => Ok(None),
+    }
+}
+
+static QUIET: OnceLock<bool> = OnceLock::new();
+
+// With --quiet, only the game itself is shown.
+pub fn quiet() -> bool {
+    QUIET.get().copied().unwrap_or(false)
+}
+
+impl Options {
+    pub fn from_args(args: &mut Vec<String>) -> Result<Options, Error> {
+        let _ = QUIET.set(take_flag(args, "--quiet"));
+        let player = match take_option(args, "--player")? {
+            Some(name) if !crate::storage::valid_name(&name) => {
+                return Err(Error::Usage(format!(
+                    "'{}' is not a valid player name (letters, digits, - and _ only)",
+                    name
+                )));
+            }
+            Some(name) => name,
+            None => profile::default_name(),
+        };
+        Ok(Options {
+            player,
+            seed: take_number(args, "--seed")?,
+            config: take_option(args, "--config")?.map_or_else(config::default_path, PathBuf::from),
+            accessible: take_flag(args, "--accessible"),
+        })
+    }
+}
+
+// Options the command doesn't know are mistakes, not arguments.
+pub fn check_options(command: &Command, args: &[String], options: &Options) -> Result<(), Error> {
+    let known = |arg: &str| {
+        command
+            .options
+            .iter()
+            .any(|(option, _)| option.split(' ').next() == Some(arg))
+    };
+    if options.seed.is_some() && !known("--seed") {
+        return Err(Error::Usage(format!(
+            "{} doesn't take --seed; see `help {}`",
+            command.name, command.name
+        )));
+    }
+    match args.iter().find(|arg| arg.starts_with("--") && !known(arg)) {
+        Some(arg) => Err(Error::Usage(format!(
+            "{} doesn't take {}; see `help {}`",
+            command.name, arg, command.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+// For commands that take nothing after their name.
+pub fn no_arguments(command: &Command, args: &[String]) -> Result<(), Error> {
+    match args.first() {
+        Some(arg) => Err(Error::Usage(format!(
+            "{} doesn't take '{}'; see `help {}`",
+            command.name, arg, command.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn print_options(options: &[(&str, &str)]) {
+    for (option, about) in options {
+        println!("  {:<20} {}", option, about);
+    }
+}
+
+pub fn help(command: Option<&Command>) {
+    let Some(command) = command else {
+        println!("Usage: guessing_game [command] [options]");
+        println!();
+        println!("Commands:");
+        for command in COMMANDS {
+            println!("  {:<14}{}", command.name, command.about);
+        }
+        println!();
+        println!("Options for every command:");
+        print_options(GLOBAL_OPTIONS);
+        println!();
+        println!("Run `guessing_game help <command>` for more about one command.");
+        return;
+    };
+    println!("Usage:");
+    for usage in command.usage {
+        println!("  guessing_game {}", usage);
+    }
+    println!();
+    println!("{}", command.about);
+    if command.name == "play" {
+        println!();
+        println!("Modes:");
+        for entry in mode::MODES {
+            println!("  {:<44} {}", entry.usage, entry.about);
+        }
+    }
+    if !command.options.is_empty() {
+        println!();
+        println!("Options:");
+        print_options(command.options);
+    }
+    println!();
+    println!("Options for every command:");
+    print_options(GLOBAL_OPTIONS);
+}
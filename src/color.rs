@@ -0,0 +1,206 @@
+use crate::access;
+use crate::game;
+use crate::i18n;
+use crate::mode::{GameMode, Score, Setup};
+use crate::theme::{self, Kind};
+use rand::Rng;
+use std::cmp::Ordering;
+use std::env;
+use std::fmt;
+
+// The same tuple struct as in structs.rs: red, green and blue channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+impl Color {
+    pub fn random(rng: &mut impl Rng) -> Color {
+        Color(
+            rng.gen_range(0, 256) as u8,
+            rng.gen_range(0, 256) as u8,
+            rng.gen_range(0, 256) as u8,
+        )
+    }
+
+    // Accepts "r,g,b" (decimal, spaces allowed) or "#rrggbb".
+    pub fn parse(input: &str) -> Result<Color, String> {
+        let input = input.trim();
+        if let Some(hex) = input.strip_prefix('#') {
+            if hex.len() != 6 || !hex.is_ascii() {
+                return Err(i18n::text("color.hex_format", &[]));
+            }
+            let channel = |i: usize| {
+                u8::from_str_radix(&hex[i..i + 2], 16)
+                    .map_err(|_| i18n::text("color.not_hex_byte", &[("byte", &&hex[i..i + 2])]))
+            };
+            return Ok(Color(channel(0)?, channel(2)?, channel(4)?));
+        }
+
+        let parts: Vec<&str> = input.split(',').map(str::trim).collect();
+        if parts.len() != 3 {
+            return Err(i18n::text("color.format", &[]));
+        }
+        let mut channels = [0u8; 3];
+        for (slot, part) in channels.iter_mut().zip(&parts) {
+            *slot = part
+                .parse()
+                .map_err(|_| i18n::text("color.not_a_channel", &[("part", part)]))?;
+        }
+        Ok(Color(channels[0], channels[1], channels[2]))
+    }
+
+    pub fn channels(&self) -> [u8; 3] {
+        [self.0, self.1, self.2]
+    }
+
+    // CIE76 delta E: euclidean distance in CIELAB space. Around 2.3 is the
+    // smallest difference most people can see.
+    pub fn distance(&self, other: &Color) -> f64 {
+        let (l1, a1, b1) = self.to_lab();
+        let (l2, a2, b2) = other.to_lab();
+        ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+    }
+
+    fn to_lab(self) -> (f64, f64, f64) {
+        let linear = |c: u8| {
+            let c = f64::from(c) / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = (linear(self.0), linear(self.1), linear(self.2));
+
+        // sRGB -> XYZ, normalised to the D65 white point
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+        let f = |t: f64| {
+            let delta: f64 = 6.0 / 29.0;
+            if t > delta.powi(3) {
+                t.cbrt()
+            } else {
+                t / (3.0 * delta * delta) + 4.0 / 29.0
+            }
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+
+    // A short block painted with this color as a 24-bit background.
+    pub fn swatch(&self) -> String {
+        format!("\x1b[48;2;{};{};{}m    \x1b[0m", self.0, self.1, self.2)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+// Terminals advertise 24-bit color support through COLORTERM. The swatches
+// only add to the per-channel answers, so they can be left out.
+fn supports_truecolor() -> bool {
+    access::color()
+        && matches!(
+            env::var("COLORTERM").as_deref(),
+            Ok("truecolor") | Ok("24bit")
+        )
+}
+
+pub struct ColorMode {
+    secret: Color,
+    truecolor: bool,
+    guesses: Vec<Color>,
+}
+
+impl ColorMode {
+    pub fn new(setup: &Setup) -> ColorMode {
+        ColorMode {
+            secret: Color::random(&mut game::seeded_rng(setup.seed)),
+            truecolor: supports_truecolor(),
+            guesses: Vec::new(),
+        }
+    }
+}
+
+impl GameMode for ColorMode {
+    fn name(&self) -> String {
+        String::from("color")
+    }
+
+    fn intro(&self) -> Vec<String> {
+        vec![i18n::text("color.intro", &[]), i18n::text("color.how", &[])]
+    }
+
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String> {
+        let guess = Color::parse(input)?;
+        self.guesses.push(guess);
+
+        let mut lines = vec![i18n::text("guess.echo", &[("guess", &guess)])];
+        if self.truecolor {
+            let (mine, target) = (
+                i18n::text("color.guess", &[]),
+                i18n::text("color.target", &[]),
+            );
+            let width = mine.chars().count().max(target.chars().count());
+            lines.push(format!("{:<width$} {}", mine, guess.swatch()));
+            lines.push(format!("{:<width$} {}", target, self.secret.swatch()));
+        }
+
+        let names = ["color.red", "color.green", "color.blue"].map(|key| i18n::text(key, &[]));
+        let secret = self.secret.channels();
+        for ((name, channel), target) in names.iter().zip(guess.channels()).zip(secret) {
+            let result = channel.cmp(&target);
+            if access::enabled() {
+                let key = match result {
+                    Ordering::Less => "access.channel_too_small",
+                    Ordering::Greater => "access.channel_too_big",
+                    Ordering::Equal => "access.channel_exact",
+                };
+                lines.push(i18n::text(key, &[("channel", name)]));
+                continue;
+            }
+            let text = match result {
+                Ordering::Less => game::hint(Ordering::Less),
+                Ordering::Greater => game::hint(Ordering::Greater),
+                Ordering::Equal => theme::paint(Kind::Win, &i18n::text("color.exact", &[])),
+            };
+            lines.push(format!("  {:<5} {}", name, text));
+        }
+
+        if self.is_finished() {
+            lines.push(i18n::plural(
+                "color.won",
+                self.guesses.len() as u64,
+                &[("color", &self.secret)],
+            ));
+        } else {
+            let distance = format!("{:.1}", guess.distance(&self.secret));
+            lines.push(i18n::text("color.distance", &[("distance", &distance)]));
+        }
+        Ok(lines)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.guesses.last() == Some(&self.secret)
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        if self.is_finished() {
+            Vec::new()
+        } else {
+            vec![i18n::text("color.reveal", &[("color", &self.secret)])]
+        }
+    }
+
+    fn score(&self) -> Score {
+        Score {
+            won: self.is_finished(),
+            attempts: self.guesses.len(),
+            points: None,
+        }
+    }
+}
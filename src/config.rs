@@ -0,0 +1,145 @@
+// Settings from config.yaml in the data directory, or the file given with
+// --config. Every field is optional, so a missing file or key just means the
+// default.
+use crate::cli::Error;
+use crate::i18n;
+use crate::theme::{self, Kind};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    // e.g. "de"; otherwise taken from LANG
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    // full sentences and no color; see access.rs
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub accessible: bool,
+    // the name of a built-in theme or one from `themes`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    // user themes: a style for each kind of message, as in theme.rs
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub themes: BTreeMap<String, BTreeMap<Kind, String>>,
+}
+
+pub fn default_path() -> PathBuf {
+    crate::storage::data_dir().join("config.yaml")
+}
+
+#[cfg(feature = "yaml")]
+pub fn load(path: &Path) -> Result<Config, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(format!("Can't read {}: {}", path.display(), e)),
+    };
+    // an empty file is an empty config, not an error
+    if text.trim().is_empty() {
+        return Ok(Config::default());
+    }
+    serde_yaml::from_str(&text).map_err(|e| format!("{} is invalid: {}", path.display(), e))
+}
+
+#[cfg(feature = "yaml")]
+fn save(path: &Path, config: &Config) -> Result<(), String> {
+    let text = serde_yaml::to_string(config).map_err(|e| e.to_string())?;
+    crate::storage::write(path, &text)
+}
+
+#[cfg(feature = "yaml")]
+fn show(path: &Path, config: &Config) -> Result<(), String> {
+    println!("# {}", path.display());
+    print!(
+        "{}",
+        serde_yaml::to_string(config).map_err(|e| e.to_string())?
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "yaml"))]
+pub fn load(_: &Path) -> Result<Config, String> {
+    Ok(Config::default())
+}
+
+#[cfg(not(feature = "yaml"))]
+fn save(_: &Path, _: &Config) -> Result<(), String> {
+    Err(String::from("Config files need the `yaml` feature"))
+}
+
+#[cfg(not(feature = "yaml"))]
+fn show(_: &Path, _: &Config) -> Result<(), String> {
+    Err(String::from("Config files need the `yaml` feature"))
+}
+
+fn unknown_key(key: &str) -> String {
+    format!(
+        "There's no setting '{}'. Settings: language, accessible, theme",
+        key
+    )
+}
+
+fn set(config: &mut Config, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "language" => {
+            let languages = i18n::languages();
+            if !languages.contains(&value) {
+                return Err(format!(
+                    "There are no messages in '{}'. Available: {}",
+                    value,
+                    languages.join(", ")
+                ));
+            }
+            config.language = Some(value.to_string());
+        }
+        "accessible" => {
+            config.accessible = match value {
+                "true" | "yes" | "on" => true,
+                "false" | "no" | "off" => false,
+                _ => return Err(format!("accessible is true or false, not '{}'", value)),
+            }
+        }
+        "theme" => {
+            theme::find(value, &config.themes)?;
+            config.theme = Some(value.to_string());
+        }
+        _ => return Err(unknown_key(key)),
+    }
+    Ok(())
+}
+
+fn unset(config: &mut Config, key: &str) -> Result<(), String> {
+    match key {
+        "language" => config.language = None,
+        "accessible" => config.accessible = false,
+        "theme" => config.theme = None,
+        _ => return Err(unknown_key(key)),
+    }
+    Ok(())
+}
+
+// config [path | set <key> <value> | unset <key>]
+pub fn run(args: &[String], path: &Path) -> Result<(), Error> {
+    let mut config = load(path)?;
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args[..] {
+        [] => Ok(show(path, &config)?),
+        ["path"] => {
+            println!("{}", path.display());
+            Ok(())
+        }
+        ["set", key, value] => {
+            set(&mut config, key, value).map_err(Error::Usage)?;
+            Ok(save(path, &config)?)
+        }
+        ["unset", key] => {
+            unset(&mut config, key).map_err(Error::Usage)?;
+            Ok(save(path, &config)?)
+        }
+        _ => Err(Error::Usage(String::from(
+            "Usage: config [path | set <key> <value> | unset <key>]",
+        ))),
+    }
+}
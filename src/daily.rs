@@ -0,0 +1,172 @@
+use crate::game::Game;
+use crate::history::{self, GameRecord};
+use crate::i18n;
+use crate::mode;
+use crate::profile::{DailyResult, Profile};
+use crate::replay::Recording;
+use std::cmp::Ordering;
+use std::time::Instant;
+
+// Days since 1970-01-01, in UTC so everyone agrees on what "today" is.
+pub fn today() -> i64 {
+    (history::now() / 86_400) as i64
+}
+
+// YYYY-MM-DD for a day number (Howard Hinnant's civil_from_days).
+pub fn format_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// The day number for YYYY-MM-DD, the other way round (days_from_civil).
+pub fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    let days_in_month = match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    if !(1..=days_in_month).contains(&day) {
+        return None;
+    }
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+// Spread consecutive days far apart before seeding (splitmix64 finaliser).
+fn seed_for(days: i64) -> u64 {
+    let mut x = (days as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+// Attempts and a line of arrows, without giving the number away.
+fn share_text(date: &str, game: &Game<u32>, won: bool) -> String {
+    let arrows: String = game
+        .history()
+        .iter()
+        .map(|(_, result)| match result {
+            Ordering::Less => "⬆️",
+            Ordering::Greater => "⬇️",
+            Ordering::Equal => "✅",
+        })
+        .collect();
+    let outcome = if won {
+        i18n::plural("daily.attempts", game.attempts() as u64, &[])
+    } else {
+        String::from("X")
+    };
+    let title = i18n::text("daily.share", &[("date", &date), ("outcome", &outcome)]);
+    format!("{}\n{}", title, arrows)
+}
+
+pub fn play(player: &str) -> Result<(), String> {
+    let mut profile = Profile::load(player)?;
+    let days = today();
+    let date = format_date(days);
+
+    if let Some(result) = profile.daily.iter().find(|r| r.date == date) {
+        if result.finished {
+            println!("{}", i18n::text("daily.played", &[("date", &date)]));
+            println!("{}", result.share);
+        } else {
+            println!("{}", i18n::text("daily.started", &[("date", &date)]));
+        }
+        return Ok(());
+    }
+
+    // Mark the day as taken before the game starts, so quitting halfway
+    // doesn't earn a second try.
+    profile.daily.push(DailyResult {
+        date: date.clone(),
+        finished: false,
+        won: false,
+        share: String::new(),
+    });
+    profile.save()?;
+
+    let intro = [
+        i18n::text("daily.intro", &[("date", &date)]),
+        i18n::text("daily.rules", &[]),
+    ];
+    let mut recording = Recording::new("daily", &intro, Game::seeded(1u32, 100, 0, seed_for(days)));
+    let start = Instant::now();
+    mode::play(&mut recording, Some(player), None)?;
+    let millis = start.elapsed().as_millis() as u64;
+    for line in recording.keep() {
+        println!("{}", line);
+    }
+    let (game, won) = (&recording.game, recording.game.won());
+
+    let share = share_text(&date, game, won);
+    if let Some(result) = profile.daily.iter_mut().find(|r| r.date == date) {
+        result.finished = true;
+        result.won = won;
+        result.share = share.clone();
+    }
+    profile.save()?;
+    for line in history::record(player, GameRecord::from_game("daily", game, won, millis)) {
+        println!("{}", line);
+    }
+
+    println!("{}", i18n::text("daily.share_intro", &[]));
+    println!("{}", share);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_round_trip() {
+        // 1899-12-31 to 2101-01-01: leap days, 1900 and 2100 (no leap day)
+        // and 2000 (one)
+        let (first, last) = (
+            parse_date("1899-12-31").unwrap(),
+            parse_date("2101-01-01").unwrap(),
+        );
+        for days in first..=last {
+            assert_eq!(parse_date(&format_date(days)), Some(days), "{}", days);
+        }
+        assert_eq!(last - first, 201 * 365 + 49 + 1);
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(parse_date("2000-02-29"), Some(11_016));
+        assert_eq!(format_date(-1), "1969-12-31");
+    }
+
+    #[test]
+    fn impossible_dates_are_rejected() {
+        for date in [
+            "2023-02-29",
+            "1900-02-29",
+            "2100-02-29",
+            "2024-13-01",
+            "2024-00-10",
+            "2024-04-31",
+            "2024-06-00",
+            "2024-06",
+            "yesterday",
+        ] {
+            assert_eq!(parse_date(date), None, "{}", date);
+        }
+        assert!(parse_date("2024-02-29").is_some());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::access;
+use crate::expr;
+use crate::game;
+use crate::i18n;
+use crate::mode::{GameMode, Score, Setup};
+use crate::theme::{self, Kind};
+use rand::{Rng, StdRng};
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy)]
+pub struct Settings {
+    // the secret moves by at most this much after each wrong guess
+    pub max_step: u32,
+    pub low: u32,
+    pub high: u32,
+}
+
+impl Settings {
+    // drift [max_step] [low] [high]
+    pub fn from_args(args: &[String]) -> Result<Settings, String> {
+        let number = |i: usize, default: u32| match args.get(i) {
+            Some(arg) => arg
+                .parse()
+                .map_err(|_| format!("'{}' is not a positive number", arg)),
+            None => Ok(default),
+        };
+        let settings = Settings {
+            max_step: number(0, 3)?,
+            low: number(1, 1)?,
+            high: number(2, 100)?,
+        };
+        if settings.low >= settings.high {
+            return Err(String::from("The low end must be below the high end"));
+        }
+        // the secret is drawn from low..high + 1
+        if settings.high == u32::MAX {
+            return Err(format!("The high end must be below {}", u32::MAX));
+        }
+        Ok(settings)
+    }
+}
+
+struct Turn {
+    guess: u32,
+    secret: u32,
+    result: Ordering,
+}
+
+pub struct Drift {
+    settings: Settings,
+    secret: u32,
+    turns: Vec<Turn>,
+    // where the secret has been, starting point first
+    path: Vec<u32>,
+    rng: StdRng,
+}
+
+impl Drift {
+    pub fn new(settings: Settings, setup: &Setup) -> Drift {
+        let mut rng = game::seeded_rng(setup.seed);
+        let secret = rng.gen_range(settings.low, settings.high + 1);
+        Drift {
+            settings,
+            secret,
+            turns: Vec::new(),
+            path: vec![secret],
+            rng,
+        }
+    }
+
+    fn won(&self) -> bool {
+        self.turns
+            .last()
+            .is_some_and(|t| t.result == Ordering::Equal)
+    }
+}
+
+impl GameMode for Drift {
+    fn name(&self) -> String {
+        String::from("drift")
+    }
+
+    fn intro(&self) -> Vec<String> {
+        let Settings {
+            max_step,
+            low,
+            high,
+        } = self.settings;
+        vec![
+            i18n::text("intro.guess", &[]),
+            i18n::text(
+                "drift.intro",
+                &[("low", &low), ("high", &high), ("step", &max_step)],
+            ),
+        ]
+    }
+
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String> {
+        let Settings {
+            max_step,
+            low,
+            high,
+        } = self.settings;
+        let vars = expr::Vars {
+            lo: i128::from(low),
+            hi: i128::from(high),
+            last: self.turns.last().map(|t| i128::from(t.guess)),
+        };
+        let guess = game::parse_number(input, &vars)?;
+        if !(low..=high).contains(&guess) {
+            return Err(i18n::text(
+                "guess.out_of_range",
+                &[("low", &low), ("high", &high)],
+            ));
+        }
+
+        let result = guess.cmp(&self.secret);
+        self.turns.push(Turn {
+            guess,
+            secret: self.secret,
+            result,
+        });
+        let mut lines = game::verdict(&guess, result);
+        if result == Ordering::Equal {
+            return Ok(lines);
+        }
+
+        // Step in i64 so a secret at 0 can still drift down and get clamped.
+        let step = self
+            .rng
+            .gen_range(-i64::from(max_step), i64::from(max_step) + 1);
+        let moved = (i64::from(self.secret) + step).clamp(i64::from(low), i64::from(high));
+        self.secret = moved as u32;
+        self.path.push(self.secret);
+        if access::enabled() && max_step > 0 {
+            lines.push(i18n::text("access.moved", &[("step", &max_step)]));
+        }
+        Ok(lines)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.won()
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if !self.won() {
+            lines.push(theme::paint(
+                Kind::Loss,
+                &i18n::text("end.reveal", &[("secret", &self.secret)]),
+            ));
+        }
+        if self.turns.is_empty() {
+            return lines;
+        }
+
+        lines.push(i18n::text("drift.table", &[]));
+        for (i, turn) in self.turns.iter().enumerate() {
+            let arrow = match turn.result {
+                Ordering::Less => i18n::text("table.too_small", &[]),
+                Ordering::Greater => i18n::text("table.too_big", &[]),
+                Ordering::Equal => i18n::text("table.hit", &[]),
+            };
+            lines.push(format!(
+                "{:>4}  {:>5}  {:>6}  {}",
+                i + 1,
+                turn.guess,
+                turn.secret,
+                arrow
+            ));
+        }
+
+        let path: Vec<String> = self.path.iter().map(u32::to_string).collect();
+        lines.push(i18n::text("drift.path", &[("path", &path.join(" -> "))]));
+        lines
+    }
+
+    fn score(&self) -> Score {
+        Score {
+            won: self.won(),
+            attempts: self.turns.len(),
+            points: None,
+        }
+    }
+}
@@ -0,0 +1,307 @@
+// Integer arithmetic for guesses, so "(37+62)/2" or "(lo+hi)/2" can be typed
+// instead of worked out. Errors come back as messages, the same way the
+// `divide` example in enums.rs reports division by zero.
+
+use crate::i18n;
+
+// Values the names `lo`, `hi` and `last` stand for.
+pub struct Vars {
+    pub lo: i128,
+    pub hi: i128,
+    pub last: Option<i128>,
+}
+
+// Deep enough for anything typed by hand, shallow enough for the stack.
+const MAX_NESTING: usize = 64;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(i128),
+    Name(String),
+    Op(char),
+}
+
+// Whether the input is meant as arithmetic at all: it has to spell out a
+// number, an operator or one of the names. "abc" or "3.5" is just not a
+// number, and the caller says so instead of explaining the grammar.
+pub fn is_expression(input: &str) -> bool {
+    tokenize(input).is_ok_and(|tokens| {
+        tokens.iter().any(|token| match token {
+            Token::Name(name) => ["lo", "hi", "last"].contains(&name.as_str()),
+            _ => true,
+        })
+    })
+}
+
+pub fn eval(input: &str, vars: &Vars) -> Result<i128, String> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        depth: 0,
+        vars,
+    };
+    let value = parser.expr()?;
+    match parser.peek() {
+        None => Ok(value),
+        Some(token) => Err(unexpected(token)),
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() {
+            let mut digits = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                digits.push(d);
+                chars.next();
+            }
+            let value = digits.parse().map_err(|_| overflow())?;
+            tokens.push(Token::Number(value));
+        } else if c.is_ascii_alphabetic() {
+            let mut name = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_alphanumeric()) {
+                name.push(d);
+                chars.next();
+            }
+            tokens.push(Token::Name(name));
+        } else if "+-*/%()".contains(c) {
+            tokens.push(Token::Op(c));
+            chars.next();
+        } else {
+            return Err(i18n::text(
+                "expr.unexpected",
+                &[("token", &format!("'{}'", c))],
+            ));
+        }
+    }
+    Ok(tokens)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(n) => n.to_string(),
+        Token::Name(name) => format!("'{}'", name),
+        Token::Op(op) => format!("'{}'", op),
+    }
+}
+
+fn unexpected(token: &Token) -> String {
+    i18n::text("expr.unexpected", &[("token", &describe(token))])
+}
+
+fn overflow() -> String {
+    i18n::text("expr.overflow", &[])
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    depth: usize,
+    vars: &'a Vars,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        self.pos += 1;
+        self.tokens.get(self.pos - 1)
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<i128, String> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value = value.checked_add(self.term()?).ok_or_else(overflow)?;
+            } else if self.eat('-') {
+                value = value.checked_sub(self.term()?).ok_or_else(overflow)?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    // term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Result<i128, String> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value = value.checked_mul(self.unary()?).ok_or_else(overflow)?;
+            } else if self.eat('/') {
+                value = divide(value, self.unary()?)?;
+            } else if self.eat('%') {
+                value = remainder(value, self.unary()?)?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    // unary := '-' unary | primary
+    fn unary(&mut self) -> Result<i128, String> {
+        if self.eat('-') {
+            self.nested(|p| p.unary()?.checked_neg().ok_or_else(overflow))
+        } else {
+            self.primary()
+        }
+    }
+
+    // primary := number | name | '(' expr ')'
+    fn primary(&mut self) -> Result<i128, String> {
+        let vars = self.vars;
+        match self.next().cloned() {
+            Some(Token::Number(n)) => Ok(n),
+            Some(Token::Name(name)) => match name.as_str() {
+                "lo" => Ok(vars.lo),
+                "hi" => Ok(vars.hi),
+                "last" => vars.last.ok_or_else(|| i18n::text("expr.no_last", &[])),
+                _ => Err(i18n::text("expr.unknown_name", &[("name", &name)])),
+            },
+            Some(Token::Op('(')) => {
+                let value = self.nested(Parser::expr)?;
+                if self.eat(')') {
+                    Ok(value)
+                } else {
+                    Err(i18n::text("expr.missing_paren", &[]))
+                }
+            }
+            Some(token) => Err(unexpected(&token)),
+            None => Err(i18n::text("expr.unexpected_end", &[])),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<i128, String>,
+    ) -> Result<i128, String> {
+        if self.depth == MAX_NESTING {
+            return Err(i18n::text("expr.too_deep", &[]));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+}
+
+fn divide(a: i128, b: i128) -> Result<i128, String> {
+    if b == 0 {
+        Err(i18n::text("expr.division_by_zero", &[]))
+    } else {
+        a.checked_div(b).ok_or_else(overflow)
+    }
+}
+
+fn remainder(a: i128, b: i128) -> Result<i128, String> {
+    if b == 0 {
+        Err(i18n::text("expr.division_by_zero", &[]))
+    } else {
+        a.checked_rem(b).ok_or_else(overflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VARS: Vars = Vars {
+        lo: 1,
+        hi: 100,
+        last: None,
+    };
+
+    fn value(input: &str) -> Result<i128, String> {
+        eval(input, &VARS)
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(value("2+3*4"), Ok(14));
+        assert_eq!(value("(2+3)*4"), Ok(20));
+        assert_eq!(value("10-4-3"), Ok(3));
+        assert_eq!(value("100/10/5"), Ok(2));
+        assert_eq!(value("7+10%4"), Ok(9));
+        assert_eq!(value("(lo+hi)/2"), Ok(50));
+    }
+
+    #[test]
+    fn unary_minus() {
+        assert_eq!(value("-3"), Ok(-3));
+        assert_eq!(value("--3"), Ok(3));
+        assert_eq!(value("2*-3"), Ok(-6));
+        assert_eq!(value("-(2+3)*2"), Ok(-10));
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let message = i18n::text("expr.division_by_zero", &[]);
+        assert_eq!(value("7/0"), Err(message.clone()));
+        assert_eq!(value("7%(3-3)"), Err(message));
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let max = i128::MAX.to_string();
+        assert_eq!(value(&max), Ok(i128::MAX));
+        assert_eq!(value(&format!("{}+1", max)), Err(overflow()));
+        assert_eq!(value(&format!("{}*2", max)), Err(overflow()));
+        assert_eq!(value(&format!("-{}-2", max)), Err(overflow()));
+        assert_eq!(value(&format!("{}0", max)), Err(overflow()));
+        assert_eq!(value(&format!("(-{}-1)/-1", max)), Err(overflow()));
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(value(&nested(MAX_NESTING)), Ok(1));
+        assert_eq!(
+            value(&nested(MAX_NESTING + 1)),
+            Err(i18n::text("expr.too_deep", &[]))
+        );
+        assert_eq!(
+            value(&"-".repeat(MAX_NESTING + 1)),
+            Err(i18n::text("expr.too_deep", &[]))
+        );
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(value("last"), Err(i18n::text("expr.no_last", &[])));
+        let vars = Vars {
+            last: Some(40),
+            ..VARS
+        };
+        assert_eq!(eval("last+1", &vars), Ok(41));
+        assert_eq!(
+            value("mid"),
+            Err(i18n::text("expr.unknown_name", &[("name", &"mid")]))
+        );
+    }
+
+    #[test]
+    fn only_arithmetic_is_an_expression() {
+        assert!(is_expression("(lo+hi)/2"));
+        assert!(is_expression("last"));
+        assert!(is_expression("abc+1"));
+        assert!(!is_expression("abc"));
+        assert!(!is_expression("3.5"));
+        assert!(!is_expression(""));
+    }
+}
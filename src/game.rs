@@ -0,0 +1,803 @@
+use crate::access;
+use crate::expr;
+use crate::i18n;
+use crate::theme::{self, Kind};
+use rand::{Rng, SeedableRng, StdRng};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+// Anything the secret can be. Like PointGeneric<T> in structs.rs, the game
+// itself doesn't care which one it holds.
+pub trait Number: Copy + Ord + fmt::Display {
+    // Names the type in saved files, e.g. replays.
+    const KIND: &'static str;
+
+    fn parse(input: &str) -> Result<Self, String>;
+
+    // Uniformly random value in low..=high.
+    fn random_between<R: Rng>(low: Self, high: Self, rng: &mut R) -> Self;
+
+    // Is `self` no further than `tolerance` from `other`?
+    fn within(self, other: Self, tolerance: Self) -> bool;
+
+    // Arithmetic in guesses is done in i128. None when the value has no
+    // integer form or doesn't fit.
+    fn to_integer(self) -> Option<i128>;
+    fn from_integer(value: i128) -> Option<Self>;
+}
+
+// Uniformly random value in 0..=span, built from two u64 draws because rand
+// has no 128-bit ranges.
+fn random_up_to<R: Rng>(span: u128, rng: &mut R) -> u128 {
+    let mut draw = || (u128::from(rng.next_u64()) << 64) | u128::from(rng.next_u64());
+    if span == u128::MAX {
+        return draw();
+    }
+    // Reject the top sliver that would make some values more likely.
+    let buckets = span + 1;
+    let limit = u128::MAX - (u128::MAX % buckets);
+    loop {
+        let value = draw();
+        if value < limit {
+            return value % buckets;
+        }
+    }
+}
+
+macro_rules! integer_number {
+    ($($t:ty),*) => {$(
+        impl Number for $t {
+            const KIND: &'static str = stringify!($t);
+
+            fn parse(input: &str) -> Result<Self, String> {
+                input.parse().map_err(|_| i18n::text("guess.not_a_number", &[]))
+            }
+
+            fn random_between<R: Rng>(low: Self, high: Self, rng: &mut R) -> Self {
+                let span = (i128::from(high) - i128::from(low)) as u128;
+                (i128::from(low) + random_up_to(span, rng) as i128) as $t
+            }
+
+            fn within(self, other: Self, tolerance: Self) -> bool {
+                (i128::from(self) - i128::from(other)).abs() <= i128::from(tolerance)
+            }
+
+            fn to_integer(self) -> Option<i128> {
+                Some(i128::from(self))
+            }
+
+            fn from_integer(value: i128) -> Option<Self> {
+                <$t>::try_from(value).ok()
+            }
+        }
+    )*};
+}
+
+integer_number!(u32, i64);
+
+impl Number for u128 {
+    const KIND: &'static str = "u128";
+
+    fn parse(input: &str) -> Result<Self, String> {
+        input
+            .parse()
+            .map_err(|_| i18n::text("guess.not_a_number", &[]))
+    }
+
+    fn random_between<R: Rng>(low: Self, high: Self, rng: &mut R) -> Self {
+        low + random_up_to(high - low, rng)
+    }
+
+    fn within(self, other: Self, tolerance: Self) -> bool {
+        self.abs_diff(other) <= tolerance
+    }
+
+    fn to_integer(self) -> Option<i128> {
+        i128::try_from(self).ok()
+    }
+
+    fn from_integer(value: i128) -> Option<Self> {
+        u128::try_from(value).ok()
+    }
+}
+
+// Fixed-precision decimal: `units` counted in steps of 10^-places.
+#[derive(Clone, Copy, Debug)]
+pub struct Decimal {
+    units: i64,
+    places: u32,
+}
+
+impl Decimal {
+    pub const MAX_PLACES: u32 = 9;
+
+    // Same value expressed with `places` digits after the point, rounding
+    // toward zero if that loses digits. None if it no longer fits.
+    pub fn rescale(self, places: u32) -> Option<Decimal> {
+        let units = if places >= self.places {
+            self.units.checked_mul(10i64.pow(places - self.places))?
+        } else {
+            self.units / 10i64.pow(self.places - places)
+        };
+        Some(Decimal { units, places })
+    }
+
+    // The value counted in steps of 10^-places; places must be at least
+    // self.places. i128 leaves room for any i64 at MAX_PLACES extra digits.
+    fn scaled(self, places: u32) -> i128 {
+        i128::from(self.units) * 10i128.pow(places - self.places)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Decimal) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        let places = self.places.max(other.places);
+        self.scaled(places).cmp(&other.scaled(places))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.places == 0 {
+            return write!(f, "{}", self.units);
+        }
+        let scale = 10u64.pow(self.places);
+        let sign = if self.units < 0 { "-" } else { "" };
+        let magnitude = self.units.unsigned_abs();
+        write!(
+            f,
+            "{}{}.{:0width$}",
+            sign,
+            magnitude / scale,
+            magnitude % scale,
+            width = self.places as usize
+        )
+    }
+}
+
+impl Number for Decimal {
+    const KIND: &'static str = "decimal";
+
+    fn parse(input: &str) -> Result<Self, String> {
+        let invalid = || i18n::text("guess.not_a_decimal", &[]);
+        let (negative, digits) = match input.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, input),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        if !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        if fraction.len() > Decimal::MAX_PLACES as usize {
+            return Err(i18n::text(
+                "guess.too_many_places",
+                &[("places", &Decimal::MAX_PLACES)],
+            ));
+        }
+
+        let units: i64 = format!("{}{}", whole, fraction)
+            .parse()
+            .map_err(|_| i18n::text("guess.too_big_a_number", &[]))?;
+        Ok(Decimal {
+            units: if negative { -units } else { units },
+            places: fraction.len() as u32,
+        })
+    }
+
+    // Expects low and high already rescaled to the game's precision.
+    fn random_between<R: Rng>(low: Self, high: Self, rng: &mut R) -> Self {
+        Decimal {
+            units: i64::random_between(low.units, high.units, rng),
+            places: low.places,
+        }
+    }
+
+    fn within(self, other: Self, tolerance: Self) -> bool {
+        let places = self.places.max(other.places).max(tolerance.places);
+        (self.scaled(places) - other.scaled(places)).abs() <= tolerance.scaled(places)
+    }
+
+    // Expressions are whole-number only.
+    fn to_integer(self) -> Option<i128> {
+        None
+    }
+
+    fn from_integer(_: i128) -> Option<Self> {
+        None
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Nightmare => "nightmare",
+        }
+    }
+
+    pub fn high(self) -> u32 {
+        match self {
+            Difficulty::Easy => 50,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 1000,
+            Difficulty::Nightmare => 10_000,
+        }
+    }
+
+    // Nightmare leaves no room for anything but perfect binary search.
+    pub fn max_attempts(self) -> Option<usize> {
+        match self {
+            Difficulty::Easy | Difficulty::Normal => None,
+            Difficulty::Hard => Some(12),
+            Difficulty::Nightmare => Some(14),
+        }
+    }
+
+    pub fn game(self, seed: u64) -> Game<u32> {
+        Game::seeded(1, self.high(), 0, seed).with_max_attempts(self.max_attempts())
+    }
+}
+
+// Everything that happens in a game, in order. The game itself is a fold
+// over these, so any earlier state can be rebuilt from a prefix.
+#[derive(Clone, PartialEq)]
+pub enum GameEvent<T> {
+    // always the first event; the seed picks the secret
+    Started {
+        low: T,
+        high: T,
+        tolerance: T,
+        max_attempts: Option<usize>,
+        seed: u64,
+    },
+    Guessed(T),
+    // "Too small" (Less) or "Too big!" (Greater) for the last guess
+    HintGiven(Ordering),
+    Won,
+    // out of attempts
+    Lost,
+    // the player left before the game was decided
+    Abandoned,
+}
+
+pub struct Game<T> {
+    pub low: T,
+    pub high: T,
+    // a guess this close to the secret wins; zero means exact
+    pub tolerance: T,
+    secret: T,
+    // every guess so far with its answer
+    history: Vec<(T, Ordering)>,
+    // highest "Too small" and lowest "Too big!" guesses so far
+    below: Option<T>,
+    above: Option<T>,
+    // None plays until the number is found
+    max_attempts: Option<usize>,
+    // the secret came from this seed, so the game can be replayed
+    pub seed: u64,
+    events: Vec<GameEvent<T>>,
+}
+
+// The same seed always gives the same secret, so a game can be shared or
+// played again.
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::from_seed(&[seed as usize, (seed >> 32) as usize][..])
+}
+
+impl<T: Number> Game<T> {
+    pub fn new(low: T, high: T, tolerance: T) -> Game<T> {
+        Game::seeded(low, high, tolerance, rand::thread_rng().next_u64())
+    }
+
+    pub fn seeded(low: T, high: T, tolerance: T, seed: u64) -> Game<T> {
+        let started = GameEvent::Started {
+            low,
+            high,
+            tolerance,
+            max_attempts: None,
+            seed,
+        };
+        Game::from_events(&[started]).expect("the game was started")
+    }
+
+    // The game as it was after `events`; None unless they begin with Started.
+    pub fn from_events(events: &[GameEvent<T>]) -> Option<Game<T>> {
+        let (
+            &GameEvent::Started {
+                low,
+                high,
+                tolerance,
+                max_attempts,
+                seed,
+            },
+            rest,
+        ) = events.split_first()?
+        else {
+            return None;
+        };
+        let mut game = Game {
+            low,
+            high,
+            tolerance,
+            secret: T::random_between(low, high, &mut seeded_rng(seed)),
+            history: Vec::new(),
+            below: None,
+            above: None,
+            max_attempts,
+            seed,
+            events: vec![events[0].clone()],
+        };
+        for event in rest {
+            game.apply(event.clone());
+        }
+        Some(game)
+    }
+
+    // The limit is part of how the game started, so the events are replayed
+    // with it in place.
+    pub fn with_max_attempts(mut self, limit: Option<usize>) -> Game<T> {
+        if let Some(GameEvent::Started { max_attempts, .. }) = self.events.first_mut() {
+            *max_attempts = limit;
+        }
+        Game::from_events(&self.events).expect("the game was started")
+    }
+
+    fn apply(&mut self, event: GameEvent<T>) {
+        if let GameEvent::Guessed(guess) = event {
+            // Equal means the guess wins, even if it is only within tolerance.
+            let result = if guess.within(self.secret, self.tolerance) {
+                Ordering::Equal
+            } else {
+                guess.cmp(&self.secret)
+            };
+            match result {
+                Ordering::Less => self.below = self.below.max(Some(guess)),
+                Ordering::Greater => self.above = Some(self.above.map_or(guess, |a| a.min(guess))),
+                Ordering::Equal => {}
+            }
+            self.history.push((guess, result));
+        }
+        self.events.push(event);
+    }
+
+    pub fn events(&self) -> &[GameEvent<T>] {
+        &self.events
+    }
+
+    // Every guess so far with its answer.
+    pub fn history(&self) -> &[(T, Ordering)] {
+        &self.history
+    }
+
+    // The highest "Too small" guess so far.
+    pub fn below(&self) -> Option<T> {
+        self.below
+    }
+
+    // The lowest "Too big!" guess so far.
+    pub fn above(&self) -> Option<T> {
+        self.above
+    }
+
+    pub fn max_attempts(&self) -> Option<usize> {
+        self.max_attempts
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.events.last(),
+            Some(GameEvent::Won | GameEvent::Lost | GameEvent::Abandoned)
+        )
+    }
+
+    pub fn won(&self) -> bool {
+        self.events.last() == Some(&GameEvent::Won)
+    }
+
+    pub fn attempts(&self) -> usize {
+        self.history.len()
+    }
+
+    // Most guesses binary search needs for this range; None for decimals.
+    pub fn optimal_attempts(&self) -> Option<u32> {
+        let size = self.high.to_integer()? - self.low.to_integer()? + 1;
+        Some(u128::BITS - (size as u128).leading_zeros())
+    }
+
+    // Was every guess the middle (either middle, for an even count) of the
+    // numbers still possible at the time?
+    pub fn followed_binary_search(&self) -> bool {
+        let (Some(mut lo), Some(mut hi)) = (self.low.to_integer(), self.high.to_integer()) else {
+            return false;
+        };
+        for &(guess, result) in &self.history {
+            let Some(guess) = guess.to_integer() else {
+                return false;
+            };
+            let middle = (lo + hi).div_euclid(2);
+            if guess != middle && guess != middle + (lo + hi).rem_euclid(2) {
+                return false;
+            }
+            match result {
+                Ordering::Less => lo = guess + 1,
+                Ordering::Greater => hi = guess - 1,
+                Ordering::Equal => {}
+            }
+        }
+        !self.history.is_empty()
+    }
+
+    pub fn out_of_attempts(&self) -> bool {
+        self.max_attempts.is_some_and(|max| self.attempts() >= max)
+    }
+
+    pub fn attempts_left(&self) -> Option<usize> {
+        self.max_attempts
+            .map(|max| max.saturating_sub(self.attempts()))
+    }
+
+    // The lowest and highest numbers the answers so far still allow. Decimals
+    // have no next number, so for them the bounds are the guesses themselves.
+    pub fn still_possible(&self) -> (T, T) {
+        let step = |bound: T, by: i128| {
+            bound
+                .to_integer()
+                .and_then(|b| T::from_integer(b + by))
+                .unwrap_or(bound)
+        };
+        let low = self.below().map_or(self.low, |b| step(b, 1));
+        let high = self.above().map_or(self.high, |a| step(a, -1));
+        (low, high)
+    }
+
+    pub fn secret(&self) -> T {
+        self.secret
+    }
+
+    // Records the guess with its answer, and the loss if that was the last
+    // attempt. Equal means the guess won.
+    pub fn guess(&mut self, guess: T) -> Ordering {
+        self.apply(GameEvent::Guessed(guess));
+        let result = self.history.last().map_or(Ordering::Equal, |&(_, r)| r);
+        if result == Ordering::Equal {
+            self.apply(GameEvent::Won);
+        } else {
+            self.apply(GameEvent::HintGiven(result));
+            if self.out_of_attempts() {
+                self.apply(GameEvent::Lost);
+            }
+        }
+        result
+    }
+
+    pub fn abandon(&mut self) {
+        if !self.is_finished() {
+            self.apply(GameEvent::Abandoned);
+        }
+    }
+
+    // A plain number, or an integer expression over lo, hi and last.
+    pub fn parse_guess(&self, input: &str) -> Result<T, String> {
+        let plain = match T::parse(input) {
+            Ok(num) => return Ok(num),
+            Err(message) => message,
+        };
+        if !expr::is_expression(input) {
+            return Err(plain);
+        }
+        let (Some(low), Some(high)) = (self.low.to_integer(), self.high.to_integer()) else {
+            return Err(plain);
+        };
+        let vars = expr::Vars {
+            lo: self.below().and_then(T::to_integer).map_or(low, |b| b + 1),
+            hi: self.above().and_then(T::to_integer).map_or(high, |a| a - 1),
+            last: self.history.last().and_then(|&(g, _)| g.to_integer()),
+        };
+        let value = expr::eval(input, &vars)?;
+        T::from_integer(value).ok_or_else(|| i18n::text("guess.too_far", &[("value", &value)]))
+    }
+}
+
+// A plain number or an expression over `vars`, for modes without a Game.
+// They check their own range afterwards.
+pub fn parse_number(input: &str, vars: &expr::Vars) -> Result<u32, String> {
+    if let Ok(num) = input.parse() {
+        return Ok(num);
+    }
+    if !expr::is_expression(input) {
+        return Err(i18n::text("guess.not_a_number", &[]));
+    }
+    let value = expr::eval(input, vars)?;
+    u32::try_from(value).map_err(|_| i18n::text("guess.too_far", &[("value", &value)]))
+}
+
+// "Too small" or "Too big!" in the player's language and theme.
+pub fn hint(result: Ordering) -> String {
+    if result.is_lt() {
+        theme::paint(Kind::TooSmall, &i18n::text("hint.too_small", &[]))
+    } else {
+        theme::paint(Kind::TooBig, &i18n::text("hint.too_big", &[]))
+    }
+}
+
+pub fn prompt() -> String {
+    theme::paint(Kind::Prompt, &i18n::text("guess.prompt", &[]))
+}
+
+pub fn won() -> String {
+    theme::paint(Kind::Win, &i18n::text("end.won", &[]))
+}
+
+// The answer to a guess for modes without a Game: the hint or "You Win!", or
+// in accessible mode a full sentence about the guess first.
+pub fn verdict(guess: &dyn fmt::Display, result: Ordering) -> Vec<String> {
+    let mut lines = Vec::new();
+    if access::enabled() {
+        let key = match result {
+            Ordering::Less => "access.too_small",
+            Ordering::Greater => "access.too_big",
+            Ordering::Equal => "access.correct",
+        };
+        lines.push(i18n::text(key, &[("guess", guess)]));
+    } else if result != Ordering::Equal {
+        lines.push(hint(result));
+    }
+    if result == Ordering::Equal {
+        lines.push(won());
+    }
+    lines
+}
+
+fn lost(text: &str) -> String {
+    theme::paint(Kind::Loss, text)
+}
+
+// The accessible form of an answer: what the guess was, what is still
+// possible and how many attempts are left, in full sentences.
+pub fn announce<T: Number>(game: &Game<T>) -> String {
+    let Some(&(guess, result)) = game.history().last() else {
+        return String::new();
+    };
+    let key = match result {
+        Ordering::Less => "access.too_small",
+        Ordering::Greater => "access.too_big",
+        Ordering::Equal if guess != game.secret => "access.close_enough",
+        Ordering::Equal => "access.correct",
+    };
+    let mut sentences = vec![i18n::text(
+        key,
+        &[("guess", &guess), ("secret", &game.secret)],
+    )];
+    if result != Ordering::Equal {
+        let (low, high) = game.still_possible();
+        sentences.push(i18n::text(
+            "access.between",
+            &[("low", &low), ("high", &high)],
+        ));
+        if let Some(left) = game.attempts_left() {
+            sentences.push(i18n::plural("access.attempts_left", left as u64, &[]));
+        }
+    }
+    sentences.join(" ")
+}
+
+// What the player sees for each event.
+fn describe<T: Number>(game: &Game<T>, event: &GameEvent<T>) -> Vec<String> {
+    if access::enabled() {
+        match event {
+            GameEvent::Guessed(_) => return Vec::new(),
+            GameEvent::HintGiven(_) => return vec![announce(game)],
+            GameEvent::Won => return vec![announce(game), won()],
+            _ => {}
+        }
+    }
+    match event {
+        GameEvent::Started { .. } => Vec::new(),
+        GameEvent::Guessed(guess) => vec![i18n::text("guess.echo", &[("guess", guess)])],
+        GameEvent::HintGiven(result) => vec![hint(*result)],
+        GameEvent::Won => {
+            let mut lines = Vec::new();
+            if game
+                .history()
+                .last()
+                .is_some_and(|&(g, _)| g != game.secret)
+            {
+                let close = i18n::text("end.close_enough", &[("secret", &game.secret)]);
+                lines.push(theme::paint(Kind::Win, &close));
+            }
+            lines.push(won());
+            lines
+        }
+        GameEvent::Lost => vec![lost(&i18n::text("end.lost", &[("secret", &game.secret)]))],
+        GameEvent::Abandoned => vec![lost(&i18n::text("end.reveal", &[("secret", &game.secret)]))],
+    }
+}
+
+// Plays one line of input and returns what the player sees. An error means
+// the line wasn't a guess, and the game is unchanged.
+pub fn answer<T: Number>(game: &mut Game<T>, input: &str) -> Result<Vec<String>, String> {
+    let guess = game.parse_guess(input)?;
+    if guess < game.low || guess > game.high {
+        return Err(i18n::text(
+            "guess.out_of_range",
+            &[("low", &game.low), ("high", &game.high)],
+        ));
+    }
+    let seen = game.events.len();
+    game.guess(guess);
+    Ok(describe_since(game, seen))
+}
+
+// Abandons the game, e.g. at the end of input, and returns what the player
+// sees.
+pub fn give_up<T: Number>(game: &mut Game<T>) -> Vec<String> {
+    let seen = game.events.len();
+    game.abandon();
+    describe_since(game, seen)
+}
+
+fn describe_since<T: Number>(game: &Game<T>, seen: usize) -> Vec<String> {
+    game.events[seen..]
+        .iter()
+        .flat_map(|event| describe(game, event))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Seed 7 hides 25 in 1..=100.
+    const SEED: u64 = 7;
+
+    // Everything a game shows, to compare a rebuilt game with a live one.
+    fn state<T: Number + fmt::Debug>(game: &Game<T>) -> impl PartialEq + fmt::Debug + use<T> {
+        (
+            game.secret(),
+            game.history().to_vec(),
+            game.below(),
+            game.above(),
+            game.still_possible(),
+            game.attempts_left(),
+            game.is_finished(),
+            game.won(),
+        )
+    }
+
+    #[test]
+    fn events_rebuild_live_play() {
+        for (guesses, max_attempts) in [
+            (&[50, 10, 30, 20, 25][..], None),
+            (&[90, 5, 60][..], Some(3)),
+            (&[40, 12, 70][..], None),
+        ] {
+            let mut game = Game::seeded(1u32, 100, 0, SEED).with_max_attempts(max_attempts);
+            let mut states = vec![state(&game)];
+            for &guess in guesses {
+                if game.is_finished() {
+                    break;
+                }
+                game.guess(guess);
+                states.push(state(&game));
+            }
+            if !game.is_finished() {
+                game.abandon();
+                states.push(state(&game));
+            }
+
+            let rebuilt = Game::from_events(game.events()).unwrap();
+            assert_eq!(state(&rebuilt), state(&game));
+            assert!(rebuilt.events() == game.events());
+            // A guess is Guessed then Won, or HintGiven and maybe Lost; the
+            // live game was looked at once it was all applied.
+            let events = game.events();
+            let prefixes: Vec<_> = (1..=events.len())
+                .filter(|&n| !matches!(events[n - 1], GameEvent::Guessed(_)))
+                .filter(|&n| events.get(n) != Some(&GameEvent::Lost))
+                .map(|n| state(&Game::from_events(&events[..n]).unwrap()))
+                .collect();
+            assert_eq!(prefixes, states);
+        }
+    }
+
+    fn decimal(input: &str) -> Decimal {
+        Decimal::parse(input).unwrap()
+    }
+
+    #[test]
+    fn decimals_parse_and_print() {
+        for (input, shown) in [
+            ("3.14", "3.14"),
+            ("-0.5", "-0.5"),
+            ("007", "7"),
+            (".25", "0.25"),
+            ("2.", "2"),
+            ("1.000000001", "1.000000001"),
+        ] {
+            assert_eq!(decimal(input).to_string(), shown, "{}", input);
+        }
+
+        let not_a_decimal = Err(i18n::text("guess.not_a_decimal", &[]));
+        for input in ["", "-", ".", "1.2.3", "1e3", "+1", "- 1", "abc"] {
+            assert_eq!(Decimal::parse(input), not_a_decimal, "{:?}", input);
+        }
+        assert_eq!(
+            Decimal::parse("0.1234567890"),
+            Err(i18n::text(
+                "guess.too_many_places",
+                &[("places", &Decimal::MAX_PLACES)]
+            ))
+        );
+        assert_eq!(
+            Decimal::parse("99999999999.999999999"),
+            Err(i18n::text("guess.too_big_a_number", &[]))
+        );
+    }
+
+    #[test]
+    fn decimals_compare_by_value() {
+        assert_eq!(decimal("1.5"), decimal("1.50"));
+        assert_eq!(decimal("2"), decimal("2.000"));
+        assert!(decimal("1.05") < decimal("1.5"));
+        assert!(decimal("-1.5") < decimal("-1.25"));
+        assert!(decimal("0.999999999") < decimal("1"));
+        assert_eq!(decimal("1.25").rescale(1), Some(decimal("1.2")));
+        assert_eq!(decimal("-1.25").rescale(1), Some(decimal("-1.2")));
+        assert_eq!(
+            decimal("9223372036.854775807").rescale(0),
+            Some(decimal("9223372036"))
+        );
+        assert_eq!(decimal("9223372037").rescale(Decimal::MAX_PLACES), None);
+    }
+
+    #[test]
+    fn decimals_within_tolerance() {
+        let secret = decimal("3.14");
+        assert!(decimal("3.1").within(secret, decimal("0.05")));
+        assert!(decimal("3.19").within(secret, decimal("0.05")));
+        assert!(!decimal("3.2").within(secret, decimal("0.05")));
+        assert!(!decimal("3.1").within(secret, decimal("0.01")));
+        assert!(secret.within(decimal("3.140"), decimal("0")));
+        assert!(decimal("-0.5").within(decimal("0.5"), decimal("1")));
+    }
+}
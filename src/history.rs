@@ -0,0 +1,87 @@
+use crate::achievements;
+use crate::game::{Difficulty, Game, Number};
+use crate::i18n;
+use crate::profile::Profile;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// One finished game, as kept in the player's profile.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GameRecord {
+    pub mode: String,
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    pub won: bool,
+    pub attempts: usize,
+    // most guesses binary search could need for the range, if it applies
+    #[serde(default)]
+    pub optimal: Option<u32>,
+    // every guess was the midpoint of what was still possible
+    #[serde(default)]
+    pub binary_search: bool,
+    // seconds since the Unix epoch, when the game ended
+    pub finished_at: u64,
+    pub millis: u64,
+}
+
+impl GameRecord {
+    // A record with only the basics filled in.
+    pub fn new(mode: &str, won: bool, attempts: usize, millis: u64) -> GameRecord {
+        GameRecord {
+            mode: mode.to_string(),
+            difficulty: None,
+            won,
+            attempts,
+            optimal: None,
+            binary_search: false,
+            finished_at: now(),
+            millis,
+        }
+    }
+
+    pub fn from_game<T: Number>(mode: &str, game: &Game<T>, won: bool, millis: u64) -> GameRecord {
+        GameRecord {
+            optimal: game.optimal_attempts(),
+            binary_search: game.followed_binary_search(),
+            ..GameRecord::new(mode, won, game.attempts(), millis)
+        }
+    }
+
+    // "liar" covers "liar-1" and "liar-3"; modes with arguments record them
+    // after a dash.
+    pub fn is_mode(&self, mode: &str) -> bool {
+        self.mode
+            .strip_prefix(mode)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+// Adds the game to the player's history, and returns the achievements it
+// unlocked to announce. Saving problems are reported the same way; they
+// never end the game.
+pub fn record(player: &str, record: GameRecord) -> Vec<String> {
+    let mut lines = Vec::new();
+    let saved = Profile::load(player).and_then(|mut profile| {
+        profile.history.push(record);
+        for achievement in achievements::unlock(&mut profile) {
+            lines.push(i18n::text(
+                "history.achievement",
+                &[
+                    ("name", &achievement.name()),
+                    ("description", &achievement.description()),
+                ],
+            ));
+        }
+        profile.save()
+    });
+    if let Err(message) = saved {
+        lines.push(i18n::text("history.save_failed", &[("error", &message)]));
+    }
+    lines
+}
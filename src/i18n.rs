@@ -0,0 +1,205 @@
+// Message catalogs in locales/<language>.txt, one `key = text` per line with
+// {name} placeholders. A plural message has one key per category, e.g.
+// intro.attempts.one and intro.attempts.other. Anything a catalog lacks is
+// taken from English.
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+const ENGLISH: &str = "en";
+
+const CATALOGS: &[(&str, &str)] = &[
+    (ENGLISH, include_str!("../locales/en.txt")),
+    ("de", include_str!("../locales/de.txt")),
+    ("fr", include_str!("../locales/fr.txt")),
+];
+
+struct Catalog {
+    language: &'static str,
+    messages: HashMap<&'static str, &'static str>,
+}
+
+static CURRENT: OnceLock<Catalog> = OnceLock::new();
+static FALLBACK: OnceLock<Catalog> = OnceLock::new();
+
+fn parse(language: &'static str, source: &'static str) -> Catalog {
+    let messages = source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, text)| (key.trim(), text.trim()))
+        .collect();
+    Catalog { language, messages }
+}
+
+fn load(language: &str) -> Option<Catalog> {
+    CATALOGS
+        .iter()
+        .find(|(name, _)| *name == language)
+        .map(|&(name, source)| parse(name, source))
+}
+
+// Every language with a catalog.
+pub fn languages() -> Vec<&'static str> {
+    CATALOGS.iter().map(|&(name, _)| name).collect()
+}
+
+// "de_DE.UTF-8" and "de" both mean German.
+fn language_of(locale: &str) -> &str {
+    locale.split(['_', '.', '@', '-']).next().unwrap_or(locale)
+}
+
+// The configured language, else the first of LC_ALL, LC_MESSAGES and LANG
+// that is set; English if none has a catalog. Messages are English until
+// this is called.
+pub fn init(configured: Option<&str>) {
+    let from_env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty());
+    let catalog = configured
+        .map(String::from)
+        .or(from_env)
+        .and_then(|locale| load(&language_of(&locale).to_lowercase()));
+    if let Some(catalog) = catalog {
+        let _ = CURRENT.set(catalog);
+    }
+}
+
+fn english() -> &'static Catalog {
+    FALLBACK.get_or_init(|| load(ENGLISH).expect("English is built in"))
+}
+
+fn current() -> &'static Catalog {
+    CURRENT.get().unwrap_or_else(english)
+}
+
+fn lookup(key: &str) -> &str {
+    current()
+        .messages
+        .get(key)
+        .or_else(|| english().messages.get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = String::from(template);
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    text
+}
+
+pub fn text(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    fill(lookup(key), args)
+}
+
+// The plural category for `n`. French counts 0 as singular; English and
+// German only 1.
+fn category(language: &str, n: u64) -> &'static str {
+    match (language, n) {
+        ("fr", 0 | 1) => "one",
+        (_, 1) => "one",
+        _ => "other",
+    }
+}
+
+// A message that depends on a count, which is also available as {n}.
+pub fn plural(key: &str, n: u64, args: &[(&str, &dyn Display)]) -> String {
+    let key = format!("{}.{}", key, category(current().language, n));
+    let mut args = args.to_vec();
+    args.push(("n", &n));
+    fill(lookup(&key), &args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_key_is_in_every_locale() {
+        let catalogs: Vec<Catalog> = CATALOGS
+            .iter()
+            .map(|&(name, source)| parse(name, source))
+            .collect();
+        for catalog in &catalogs {
+            for other in &catalogs {
+                for key in other.messages.keys() {
+                    assert!(
+                        catalog.messages.contains_key(key),
+                        "{} is in {} but not in {}",
+                        key,
+                        other.language,
+                        catalog.language
+                    );
+                }
+            }
+        }
+    }
+
+    // Every string literal in the sources that looks like `namespace.key` for
+    // a namespace the catalogs use, and isn't a file name, has to be a
+    // message (or the stem of a plural one) in every catalog.
+    #[test]
+    fn every_key_in_the_code_is_in_every_locale() {
+        let catalogs: Vec<Catalog> = CATALOGS
+            .iter()
+            .map(|&(name, source)| parse(name, source))
+            .collect();
+        let namespaces: Vec<&str> = catalogs[0]
+            .messages
+            .keys()
+            .filter_map(|key| key.split('.').next())
+            .collect();
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(src).unwrap() {
+            let path = entry.unwrap().path();
+            let source = std::fs::read_to_string(&path).unwrap();
+            for literal in source.split('"').skip(1).step_by(2) {
+                let looks_like_key = literal.contains('.')
+                    && literal
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c == '_' || c == '.')
+                    && namespaces.contains(&literal.split('.').next().unwrap())
+                    && !["json", "yaml", "txt"].contains(&literal.rsplit('.').next().unwrap());
+                if !looks_like_key {
+                    continue;
+                }
+                checked += 1;
+                for catalog in &catalogs {
+                    let has = |key: String| catalog.messages.contains_key(key.as_str());
+                    assert!(
+                        has(literal.to_string())
+                            || (has(format!("{}.one", literal))
+                                && has(format!("{}.other", literal))),
+                        "{} (used in {}) is not in {}",
+                        literal,
+                        path.display(),
+                        catalog.language
+                    );
+                }
+            }
+        }
+        assert!(checked > 100, "only {} keys found in the sources", checked);
+    }
+
+    #[test]
+    fn plural_categories() {
+        assert_eq!(category("en", 0), "other");
+        assert_eq!(category("en", 1), "one");
+        assert_eq!(category("de", 3), "other");
+        assert_eq!(category("fr", 0), "one");
+        assert_eq!(category("fr", 2), "other");
+    }
+
+    #[test]
+    fn locale_names() {
+        assert_eq!(language_of("de_DE.UTF-8"), "de");
+        assert_eq!(language_of("fr"), "fr");
+        assert_eq!(language_of("C"), "C");
+    }
+}
@@ -0,0 +1,111 @@
+// Every player ranked on the games in their profile. Practice games are
+// never recorded, so they never count.
+use crate::history::GameRecord;
+use crate::i18n;
+use crate::profile;
+
+pub struct Settings {
+    // only games of this mode, e.g. "classic" or "daily"
+    mode: Option<String>,
+    top: usize,
+}
+
+impl Settings {
+    // leaderboard [--mode MODE] [--top N]
+    pub fn from_args(args: &[String]) -> Result<Settings, String> {
+        let mut settings = Settings {
+            mode: None,
+            top: 10,
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--mode" => {
+                    let mode = args.next().ok_or("--mode needs a mode")?;
+                    settings.mode = Some(mode.clone());
+                }
+                "--top" => {
+                    settings.top = args
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|&n| n > 0)
+                        .ok_or("--top needs a number above 0")?;
+                }
+                _ => return Err(format!("Unexpected '{}'", arg)),
+            }
+        }
+        Ok(settings)
+    }
+}
+
+struct Row {
+    player: String,
+    games: usize,
+    won: usize,
+    // average attempts over the games won
+    average: Option<f64>,
+}
+
+impl Row {
+    fn new(player: String, games: &[&GameRecord]) -> Row {
+        let wins: Vec<usize> = games
+            .iter()
+            .filter(|game| game.won)
+            .map(|game| game.attempts)
+            .collect();
+        Row {
+            player,
+            games: games.len(),
+            won: wins.len(),
+            average: (!wins.is_empty())
+                .then(|| wins.iter().sum::<usize>() as f64 / wins.len() as f64),
+        }
+    }
+}
+
+pub fn show(settings: Settings) -> Result<(), String> {
+    let mut rows: Vec<Row> = profile::all()?
+        .into_iter()
+        .map(|profile| {
+            let games: Vec<&GameRecord> = profile
+                .history
+                .iter()
+                .filter(|game| settings.mode.as_ref().is_none_or(|mode| game.is_mode(mode)))
+                .collect();
+            Row::new(profile.name.clone(), &games)
+        })
+        .filter(|row| row.games > 0)
+        .collect();
+    if rows.is_empty() {
+        println!("{}", i18n::text("leaderboard.none", &[]));
+        return Ok(());
+    }
+    // most wins first, then fewest attempts per win
+    rows.sort_by(|a, b| {
+        b.won.cmp(&a.won).then(
+            a.average
+                .unwrap_or(f64::MAX)
+                .total_cmp(&b.average.unwrap_or(f64::MAX)),
+        )
+    });
+
+    if let Some(mode) = &settings.mode {
+        println!("{}", i18n::text("leaderboard.mode", &[("mode", mode)]));
+    }
+    println!("{}", i18n::text("leaderboard.table", &[]));
+    for (rank, row) in rows.iter().take(settings.top).enumerate() {
+        let average = row
+            .average
+            .map_or(String::from("-"), |a| format!("{:.2}", a));
+        println!(
+            "{:>4}  {:<20}  {:>5}  {:>4}  {:>4.0}%  {:>5}",
+            rank + 1,
+            row.player,
+            row.games,
+            row.won,
+            100.0 * row.won as f64 / row.games as f64,
+            average
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use crate::cli::Error;
+use crate::expr;
+use crate::game;
+use crate::i18n;
+use crate::mode::{self, GameMode, Score, Setup};
+use crate::solver;
+use crate::theme::{self, Kind};
+use rand::{Rng, StdRng};
+use std::cmp::Ordering;
+
+const LOW: u32 = 1;
+const HIGH: u32 = 100;
+// Above this the exhaustive bound check gets slow; three lies is plenty hard.
+pub const MAX_LIES: u32 = 3;
+
+struct Answer {
+    guess: u32,
+    reported: Ordering,
+    lie: bool,
+}
+
+pub struct Liar {
+    max_lies: u32,
+    max_attempts: u32,
+    secret: u32,
+    answers: Vec<Answer>,
+    lies_left: u32,
+    rng: StdRng,
+}
+
+impl Liar {
+    // liar [lies]
+    pub fn new(args: &[String], setup: &Setup) -> Result<Liar, Error> {
+        mode::at_most("liar", args, 1)?;
+        let max_lies = match args.first() {
+            Some(lies) => lies
+                .parse()
+                .ok()
+                .filter(|&k| k <= MAX_LIES)
+                .ok_or_else(|| {
+                    Error::Usage(format!(
+                        "'{}' is not a number of lies from 0 to {}",
+                        lies, MAX_LIES
+                    ))
+                })?,
+            None => 1,
+        };
+        let mut rng = game::seeded_rng(setup.seed);
+        Ok(Liar {
+            max_lies,
+            max_attempts: solver::worst_case(LOW, HIGH, max_lies),
+            secret: rng.gen_range(LOW, HIGH + 1),
+            answers: Vec::new(),
+            lies_left: max_lies,
+            rng,
+        })
+    }
+
+    fn won(&self) -> bool {
+        self.answers
+            .last()
+            .is_some_and(|answer| answer.reported == Ordering::Equal)
+    }
+}
+
+impl GameMode for Liar {
+    fn name(&self) -> String {
+        format!("liar-{}", self.max_lies)
+    }
+
+    fn intro(&self) -> Vec<String> {
+        vec![
+            i18n::text("intro.guess", &[]),
+            i18n::plural("liar.lies", u64::from(self.max_lies), &[]),
+            i18n::text("liar.truth", &[]),
+            i18n::plural("liar.attempts", u64::from(self.max_attempts), &[]),
+        ]
+    }
+
+    fn prompt(&self) -> String {
+        let left = self.max_attempts - self.answers.len() as u32;
+        let prompt = i18n::text("liar.prompt", &[("left", &left)]);
+        theme::paint(Kind::Prompt, &prompt)
+    }
+
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String> {
+        // the answers may lie, so lo and hi stay the whole range
+        let vars = expr::Vars {
+            lo: i128::from(LOW),
+            hi: i128::from(HIGH),
+            last: self.answers.last().map(|a| i128::from(a.guess)),
+        };
+        let guess = match game::parse_number(input, &vars)? {
+            num if (LOW..=HIGH).contains(&num) => num,
+            _ => {
+                return Err(i18n::text(
+                    "guess.out_of_range",
+                    &[("low", &LOW), ("high", &HIGH)],
+                ));
+            }
+        };
+
+        let truth = guess.cmp(&self.secret);
+        let lie = truth != Ordering::Equal && self.lies_left > 0 && self.rng.gen_weighted_bool(3);
+        let reported = if lie { truth.reverse() } else { truth };
+        if lie {
+            self.lies_left -= 1;
+        }
+        self.answers.push(Answer {
+            guess,
+            reported,
+            lie,
+        });
+
+        Ok(game::verdict(&guess, reported))
+    }
+
+    fn is_finished(&self) -> bool {
+        self.won() || self.answers.len() as u32 >= self.max_attempts
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if !self.won() {
+            let lost = i18n::text("end.lost", &[("secret", &self.secret)]);
+            lines.push(theme::paint(Kind::Loss, &lost));
+        }
+
+        lines.push(i18n::text("liar.answers", &[]));
+        for (turn, answer) in self.answers.iter().enumerate() {
+            let text = match answer.reported {
+                Ordering::Less => i18n::text("hint.too_small", &[]),
+                Ordering::Greater => i18n::text("hint.too_big", &[]),
+                Ordering::Equal => i18n::text("end.won", &[]),
+            };
+            let marker = if answer.lie {
+                format!("  <- {}", i18n::text("liar.lie", &[]))
+            } else {
+                String::new()
+            };
+            lines.push(format!(
+                "  {:>2}. {:>3}: {}{}",
+                turn + 1,
+                answer.guess,
+                text,
+                marker
+            ));
+        }
+        let told = self.max_lies - self.lies_left;
+        lines.push(i18n::plural("liar.told", u64::from(told), &[]));
+        lines
+    }
+
+    fn score(&self) -> Score {
+        Score {
+            won: self.won(),
+            attempts: self.answers.len(),
+            points: None,
+        }
+    }
+}
@@ -1,29 +1,166 @@
 extern crate rand;
 
-use rand::Rng;
-use std::cmp::Ordering;
-use std::io;
+mod access;
+mod achievements;
+#[cfg(feature = "json")]
+mod arena;
+mod classic;
+mod cli;
+mod color;
+mod config;
+mod daily;
+mod drift;
+mod expr;
+mod game;
+mod history;
+mod i18n;
+mod leaderboard;
+mod liar;
+mod mode;
+mod multi;
+mod practice;
+mod profile;
+#[cfg(feature = "json")]
+mod protocol;
+mod replay;
+mod save;
+#[cfg(feature = "script")]
+mod script;
+mod simulate;
+mod solver;
+mod stats;
+mod storage;
+mod theme;
+mod timeattack;
+mod timer;
+mod tournament;
+mod wager;
 
-fn main() {
-    println!("Guess the number!");
-    let secret_number = rand::thread_rng().gen_range(1, 101);
-    println!("The secret number is: {}", secret_number);
-
-    println!("Please input your guess.");
+use cli::{Command, Error, Options};
+use std::env;
+use std::process;
 
-    let mut guess = String::new();
+fn main() {
+    let code = match run(env::args().skip(1).collect()) {
+        Ok(()) => 0,
+        Err(Error::Usage(message)) => {
+            eprintln!("{}", message);
+            eprintln!("Run `guessing_game help` for usage.");
+            2
+        }
+        Err(Error::Failed(message)) => {
+            eprintln!("{}", message);
+            1
+        }
+    };
+    process::exit(code);
+}
 
-    io::stdin()
-        .read_line(&mut guess)
-        .expect("Failed to read line");
+fn run(mut args: Vec<String>) -> Result<(), Error> {
+    let options = Options::from_args(&mut args)?;
+    let config = config::load(&options.config)?;
+    i18n::init(config.language.as_deref());
+    access::init(options.accessible || config.accessible);
+    theme::init(&config)?;
 
-    let guess: u32 = guess.trim().parse().expect("Please type a number!");
+    // no command plays a classic game
+    let name = if args.is_empty() {
+        String::from("play")
+    } else {
+        args.remove(0)
+    };
+    let name = match name.as_str() {
+        "--help" | "-h" => "help",
+        // PROTOCOL.md has always called it this
+        "--protocol" => "protocol",
+        name => name,
+    };
+    let command =
+        cli::find(name).ok_or_else(|| Error::Usage(format!("There's no command '{}'", name)))?;
+    if cli::take_flag(&mut args, "--help") || cli::take_flag(&mut args, "-h") {
+        cli::help(Some(command));
+        return Ok(());
+    }
+    cli::check_options(command, &args, &options)?;
+    dispatch(command, args, &options)
+}
 
-    println!("you guessed: {}", guess);
+fn dispatch(command: &Command, args: Vec<String>, options: &Options) -> Result<(), Error> {
+    let player = options.player.as_str();
+    match command.name {
+        "play" => play(args, options)?,
+        "practice" => play([vec![String::from("practice")], args].concat(), options)?,
+        "daily" => {
+            cli::no_arguments(command, &args)?;
+            daily::play(player)?;
+        }
+        "resume" => {
+            cli::no_arguments(command, &args)?;
+            save::resume(player)?;
+        }
+        "stats" => {
+            let settings = stats::Settings::from_args(&args).map_err(Error::Usage)?;
+            stats::show(player, settings)?;
+        }
+        "leaderboard" => {
+            let settings = leaderboard::Settings::from_args(&args).map_err(Error::Usage)?;
+            leaderboard::show(settings)?;
+        }
+        "replay" => replay::run(&args)?,
+        "simulate" => {
+            let settings =
+                simulate::Settings::from_args(&args, options.seed).map_err(Error::Usage)?;
+            simulate::run(settings);
+        }
+        "profile" => profile::run(&args, player)?,
+        "achievements" => {
+            cli::no_arguments(command, &args)?;
+            achievements::list(player)?;
+        }
+        "tournament" => tournament::run(&args)?,
+        #[cfg(feature = "json")]
+        "arena" => {
+            let settings = arena::Settings::from_args(&args, options.seed).map_err(Error::Usage)?;
+            arena::run(settings);
+        }
+        #[cfg(feature = "json")]
+        "protocol" => {
+            cli::no_arguments(command, &args)?;
+            protocol::run();
+        }
+        "config" => config::run(&args, &options.config)?,
+        "help" => match args.first() {
+            Some(name) => {
+                let command = cli::find(name)
+                    .ok_or_else(|| Error::Usage(format!("There's no command '{}'", name)))?;
+                cli::help(Some(command));
+            }
+            None => cli::help(None),
+        },
+        name => unreachable!("{} is in cli::COMMANDS but not handled", name),
+    }
+    Ok(())
+}
 
-    match guess.cmp(&secret_number) {
-        Ordering::Less => println!("Too small"),
-        Ordering::Greater => println!("Too big!"),
-        Ordering::Equal => println!("You Win!"),
+// play [mode [args]]
+fn play(mut args: Vec<String>, options: &Options) -> Result<(), Error> {
+    let setup = mode::Setup {
+        player: options.player.clone(),
+        seed: options.seed.unwrap_or_else(rand::random),
+        seeded: options.seed.is_some(),
+        input_file: cli::take_option(&mut args, "--input-file")?,
+    };
+    let (name, rest) = match args.split_first() {
+        Some((name, rest)) => (name.as_str(), rest),
+        None => ("normal", &[][..]),
+    };
+    let entry = mode::find(name)
+        .ok_or_else(|| Error::Usage(format!("There's no game called '{}'", name)))?;
+    let mut game = (entry.create)(rest, &setup)?;
+    if game.saves() && !save::may_replace(&setup.player) {
+        return Ok(());
     }
+    let player = setup.recorded().then_some(setup.player.as_str());
+    mode::play(&mut *game, player, setup.input_file.as_deref())?;
+    Ok(())
 }
@@ -0,0 +1,473 @@
+use crate::classic;
+use crate::cli::{self, Error};
+use crate::color;
+use crate::drift;
+use crate::game::{self, Difficulty};
+use crate::history::{self, GameRecord};
+use crate::i18n;
+use crate::liar;
+use crate::multi;
+use crate::practice;
+#[cfg(feature = "script")]
+use crate::script;
+use crate::theme::{self, Kind};
+use crate::timeattack;
+use crate::timer::{Input, TimedInput};
+use crate::wager;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::time::Instant;
+
+// What every mode gets besides its own arguments.
+pub struct Setup {
+    pub player: String,
+    // picks the secrets, so the same seed plays the same game
+    pub seed: u64,
+    // the seed came from --seed, so the secrets are known in advance; nothing
+    // is saved or recorded
+    pub seeded: bool,
+    // guesses come from this file instead of the keyboard; nothing is saved
+    // or recorded
+    pub input_file: Option<String>,
+}
+
+impl Setup {
+    // Whether the game counts: saved, recorded and kept as a replay.
+    pub fn recorded(&self) -> bool {
+        self.input_file.is_none() && !self.seeded
+    }
+}
+
+pub struct Score {
+    pub won: bool,
+    pub attempts: usize,
+    // for modes that score more than winning or losing
+    pub points: Option<u32>,
+}
+
+// One way to play. `play` only talks to modes through this, so a new one is
+// an impl plus an entry in MODES.
+pub trait GameMode {
+    // As recorded in the player's history.
+    fn name(&self) -> String;
+
+    fn intro(&self) -> Vec<String>;
+
+    fn prompt(&self) -> String {
+        game::prompt()
+    }
+
+    // Answers one line of input with the lines to show. An error is shown
+    // to the player, who then tries again; it doesn't count as a turn.
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String>;
+
+    fn is_finished(&self) -> bool;
+
+    // Called once the game is over or abandoned; the lines are shown last.
+    fn finish(&mut self) -> Vec<String>;
+
+    fn score(&self) -> Score;
+
+    // What goes in the player's history; None records nothing.
+    fn record(&self, millis: u64) -> Option<GameRecord> {
+        let score = self.score();
+        (score.attempts > 0)
+            .then(|| GameRecord::new(&self.name(), score.won, score.attempts, millis))
+    }
+
+    // Modes against the clock: the next input has to come by then.
+    fn deadline(&self) -> Option<Instant> {
+        None
+    }
+
+    // Shown in front of a timed prompt, e.g. the time left.
+    fn status(&self) -> String {
+        String::new()
+    }
+
+    // The deadline passed without a guess.
+    fn time_up(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+
+    // Modes that can be resumed save after every input, ask on Ctrl-C
+    // whether to keep the game and keep it without asking at the end of
+    // input.
+    fn saves(&self) -> bool {
+        false
+    }
+
+    // Ok(false) when there was nothing to save.
+    fn save(&mut self) -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+// Builds a mode from the arguments after its name.
+pub type Create = fn(&[String], &Setup) -> Result<Box<dyn GameMode>, Error>;
+
+pub struct Entry {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub about: &'static str,
+    pub create: Create,
+}
+
+pub const MODES: &[Entry] = &[
+    Entry {
+        name: "easy",
+        usage: "easy",
+        about: "1 to 50",
+        create: |args, setup| classic::difficulty(Difficulty::Easy, args, setup),
+    },
+    Entry {
+        name: "normal",
+        usage: "normal",
+        about: "1 to 100; what plays with no mode at all",
+        create: |args, setup| classic::difficulty(Difficulty::Normal, args, setup),
+    },
+    Entry {
+        name: "hard",
+        usage: "hard",
+        about: "1 to 1000 in 12 attempts",
+        create: |args, setup| classic::difficulty(Difficulty::Hard, args, setup),
+    },
+    Entry {
+        name: "nightmare",
+        usage: "nightmare",
+        about: "1 to 10000 in 14 attempts",
+        create: |args, setup| classic::difficulty(Difficulty::Nightmare, args, setup),
+    },
+    Entry {
+        name: "range",
+        usage: "range <low> <high>",
+        about: "any whole numbers, up to u128",
+        create: classic::range,
+    },
+    Entry {
+        name: "decimal",
+        usage: "decimal <low> <high> [places] [tolerance]",
+        about: "close enough wins",
+        create: classic::decimal,
+    },
+    Entry {
+        name: "practice",
+        usage: "practice [high]",
+        about: "undo and hints, nothing recorded",
+        create: |args, setup| Ok(Box::new(practice::Practice::new(args, setup)?)),
+    },
+    Entry {
+        name: "liar",
+        usage: "liar [lies]",
+        about: "some answers are lies",
+        create: |args, setup| Ok(Box::new(liar::Liar::new(args, setup)?)),
+    },
+    Entry {
+        name: "drift",
+        usage: "drift [max_step] [low] [high]",
+        about: "the number moves after every wrong guess",
+        create: |args, setup| {
+            at_most("drift", args, 3)?;
+            let settings = drift::Settings::from_args(args).map_err(Error::Usage)?;
+            Ok(Box::new(drift::Drift::new(settings, setup)))
+        },
+    },
+    Entry {
+        name: "timeattack",
+        usage: "timeattack [total_secs] [per_guess_secs]",
+        about: "beat the clock",
+        create: |args, setup| {
+            at_most("timeattack", args, 2)?;
+            let settings = timeattack::Settings::from_args(args).map_err(Error::Usage)?;
+            Ok(Box::new(timeattack::TimeAttack::new(settings, setup)))
+        },
+    },
+    Entry {
+        name: "wager",
+        usage: "wager [house_edge_percent]",
+        about: "bet on finding it in few attempts",
+        create: |args, setup| {
+            at_most("wager", args, 1)?;
+            let settings = wager::Settings::from_args(args).map_err(Error::Usage)?;
+            Ok(Box::new(wager::Wager::new(settings, setup)?))
+        },
+    },
+    Entry {
+        name: "color",
+        usage: "color",
+        about: "guess an RGB color",
+        create: |args, setup| {
+            at_most("color", args, 0)?;
+            Ok(Box::new(color::ColorMode::new(setup)))
+        },
+    },
+    Entry {
+        name: "multi",
+        usage: "multi [count]",
+        about: "find several numbers at once",
+        create: |args, setup| Ok(Box::new(multi::Multi::new(args, setup)?)),
+    },
+    #[cfg(feature = "script")]
+    Entry {
+        name: "script",
+        usage: "script <file.rhai>",
+        about: "house rules written in Rhai",
+        create: |args, setup| Ok(Box::new(script::ScriptMode::new(args, setup)?)),
+    },
+];
+
+// Modes take their arguments in order; anything past `max` is a mistake.
+pub fn at_most(name: &str, args: &[String], max: usize) -> Result<(), Error> {
+    match args.get(max) {
+        Some(extra) => Err(Error::Usage(format!(
+            "play {} doesn't take '{}'",
+            name, extra
+        ))),
+        None => Ok(()),
+    }
+}
+
+pub fn find(name: &str) -> Option<&'static Entry> {
+    MODES.iter().find(|entry| entry.name == name)
+}
+
+// Plays `mode` from the keyboard, or from `input_file` without saving or
+// recording anything. The game goes in `player`'s history unless that's None.
+pub fn play(
+    mode: &mut dyn GameMode,
+    player: Option<&str>,
+    input_file: Option<&str>,
+) -> Result<(), String> {
+    play_to(mode, player, input_file, &mut io::stdout())
+}
+
+// `play`, with everything the player sees going to `output`.
+pub fn play_to(
+    mode: &mut dyn GameMode,
+    player: Option<&str>,
+    input_file: Option<&str>,
+    output: &mut impl Write,
+) -> Result<(), String> {
+    let file = match input_file {
+        Some(path) => Some(File::open(path).map_err(|e| format!("Can't read {}: {}", path, e))?),
+        None => None,
+    };
+    let start = Instant::now();
+    let saved = match file {
+        Some(file) => {
+            let mut lines = BufReader::new(file).lines();
+            play_from(
+                mode,
+                |_| match lines.next() {
+                    Some(Ok(line)) => Input::Line(line),
+                    _ => Input::Closed,
+                },
+                output,
+            )
+        }
+        None => {
+            let keyboard = TimedInput::new();
+            play_from(
+                mode,
+                |mode| {
+                    if mode.deadline().is_some() {
+                        print!("{} > ", mode.status());
+                        let _ = io::stdout().flush();
+                    }
+                    keyboard.read_line(mode.deadline(), || mode.status())
+                },
+                output,
+            )
+        }
+    };
+    let Some(player) = player.filter(|_| !saved && input_file.is_none()) else {
+        return Ok(());
+    };
+    let millis = start.elapsed().as_millis() as u64;
+    if let Some(record) = mode.record(millis) {
+        for line in history::record(player, record) {
+            say(output, &line);
+        }
+    }
+    Ok(())
+}
+
+// The intro, the game with input from `next`, and how it ended. Returns true
+// if the player stopped to resume later.
+pub fn play_from(
+    mode: &mut dyn GameMode,
+    next: impl FnMut(&dyn GameMode) -> Input,
+    output: &mut impl Write,
+) -> bool {
+    if !cli::quiet() {
+        for line in mode.intro() {
+            say(output, &line);
+        }
+    }
+    if run(mode, next, output) {
+        return true;
+    }
+    for line in mode.finish() {
+        say(output, &line);
+    }
+    if let Some(points) = mode.score().points {
+        say(output, &i18n::text("end.score", &[("points", &points)]));
+    }
+    false
+}
+
+fn say(output: &mut impl Write, line: &str) {
+    let _ = writeln!(output, "{}", line);
+}
+
+// The loop every mode shares. Returns true if the player stopped to resume
+// later.
+fn run(
+    mode: &mut dyn GameMode,
+    mut next: impl FnMut(&dyn GameMode) -> Input,
+    output: &mut impl Write,
+) -> bool {
+    while !mode.is_finished() {
+        say(output, &mode.prompt());
+        let _ = output.flush();
+
+        let keep = match next(mode) {
+            Input::Line(line) => {
+                match mode.evaluate(line.trim()) {
+                    Ok(lines) => {
+                        for line in lines {
+                            say(output, &line);
+                        }
+                    }
+                    Err(message) => say(output, &theme::paint(Kind::Error, &message)),
+                }
+                if let Err(message) = mode.save() {
+                    say(output, &i18n::text("save.failed", &[("error", &message)]));
+                }
+                continue;
+            }
+            Input::TimedOut => {
+                for line in mode.time_up() {
+                    say(output, &line);
+                }
+                continue;
+            }
+            Input::Interrupted if mode.saves() => {
+                say(output, "");
+                say(output, &i18n::text("save.ask", &[]));
+                let _ = output.flush();
+                match next(mode) {
+                    // no, nein, non
+                    Input::Line(answer) => !answer.trim().to_lowercase().starts_with('n'),
+                    _ => true,
+                }
+            }
+            Input::Interrupted => false,
+            Input::Closed => true,
+        };
+        if keep && mode.saves() {
+            match mode.save() {
+                Ok(true) => {
+                    say(output, &i18n::text("save.saved", &[]));
+                    return true;
+                }
+                Ok(false) => {}
+                Err(message) => say(output, &i18n::text("save.failed", &[("error", &message)])),
+            }
+        }
+        return false;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classic::Classic;
+    use crate::game::Game;
+
+    // Seed 7 hides 25 in 1..=100.
+    const SEED: u64 = 7;
+
+    fn golden_path(name: &str, extension: &str) -> String {
+        format!(
+            "{}/tests/golden/{}.{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name,
+            extension
+        )
+    }
+
+    fn setup(name: &str) -> Setup {
+        Setup {
+            player: String::from("golden"),
+            seed: SEED,
+            seeded: true,
+            input_file: Some(golden_path(name, "in")),
+        }
+    }
+
+    // The mode `play <args>` would play for tests/golden/<name>.in.
+    fn create(name: &str, args: &[&str]) -> Box<dyn GameMode> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let entry = find(&args[0]).unwrap();
+        (entry.create)(&args[1..], &setup(name)).unwrap_or_else(|_| panic!("{:?}", args))
+    }
+
+    // Plays tests/golden/<name>.in as --input-file and checks the output
+    // against <name>.out. Returns whether the game was won.
+    fn golden(name: &str, mode: &mut dyn GameMode) -> bool {
+        let mut output = Vec::new();
+        play_to(
+            mode,
+            Some("golden"),
+            Some(&golden_path(name, "in")),
+            &mut output,
+        )
+        .unwrap();
+        let expected = std::fs::read_to_string(golden_path(name, "out")).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected, "{}", name);
+        mode.score().won
+    }
+
+    #[test]
+    fn win() {
+        assert!(golden("win", &mut *create("win", &["normal"])));
+    }
+
+    #[test]
+    fn loss() {
+        let intro = vec![
+            i18n::text("intro.range", &[("low", &1), ("high", &100)]),
+            i18n::plural("intro.attempts", 3, &[]),
+        ];
+        let game = Game::seeded(1u32, 100, 0, SEED).with_max_attempts(Some(3));
+        let mut mode = Classic::new(&setup("loss"), None, "range", intro, game);
+        assert!(!golden("loss", &mut mode));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(golden("invalid", &mut *create("invalid", &["normal"])));
+    }
+
+    #[test]
+    fn end_of_input() {
+        assert!(!golden("eof", &mut *create("eof", &["normal"])));
+    }
+
+    #[test]
+    fn other_modes() {
+        assert!(golden("practice", &mut *create("practice", &["practice"])));
+        assert!(golden("liar", &mut *create("liar", &["liar", "1"])));
+        assert!(golden(
+            "drift",
+            &mut *create("drift", &["drift", "1", "1", "20"])
+        ));
+        assert!(!golden("multi", &mut *create("multi", &["multi", "2"])));
+        assert!(golden(
+            "decimal",
+            &mut *create("decimal", &["decimal", "0", "1", "1"])
+        ));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::access;
+use crate::cli::Error;
+use crate::expr;
+use crate::game;
+use crate::i18n;
+use crate::mode::{self, GameMode, Score, Setup};
+use crate::theme::{self, Kind};
+use rand::Rng;
+use std::cmp::Ordering;
+
+const LOW: u32 = 1;
+const HIGH: u32 = 100;
+pub const MAX_SECRETS: usize = 10;
+
+// Each secret is worth this much; every guess that finds nothing costs MISS_COST.
+const SECRET_POINTS: u32 = 100;
+const MISS_COST: u32 = 10;
+
+struct Turn {
+    guess: u32,
+    below: usize,
+    equal: usize,
+    above: usize,
+    found: bool,
+}
+
+struct Secret {
+    value: u32,
+    // turn (1-based) on which it was found
+    found_on: Option<usize>,
+}
+
+pub struct Multi {
+    secrets: Vec<Secret>,
+    turns: Vec<Turn>,
+}
+
+impl Multi {
+    // multi [count]
+    pub fn new(args: &[String], setup: &Setup) -> Result<Multi, Error> {
+        mode::at_most("multi", args, 1)?;
+        let count = match args.first() {
+            Some(count) => count
+                .parse()
+                .ok()
+                .filter(|k| (1..=MAX_SECRETS).contains(k))
+                .ok_or_else(|| {
+                    Error::Usage(format!(
+                        "'{}' is not a count from 1 to {}",
+                        count, MAX_SECRETS
+                    ))
+                })?,
+            None => 3,
+        };
+        let mut rng = game::seeded_rng(setup.seed);
+        let mut secrets: Vec<Secret> = Vec::new();
+        while secrets.len() < count {
+            let value = rng.gen_range(LOW, HIGH + 1);
+            if secrets.iter().all(|s| s.value != value) {
+                secrets.push(Secret {
+                    value,
+                    found_on: None,
+                });
+            }
+        }
+        secrets.sort_by_key(|s| s.value);
+        Ok(Multi {
+            secrets,
+            turns: Vec::new(),
+        })
+    }
+
+    fn found(&self) -> usize {
+        self.secrets.iter().filter(|s| s.found_on.is_some()).count()
+    }
+}
+
+impl GameMode for Multi {
+    fn name(&self) -> String {
+        format!("multi-{}", self.secrets.len())
+    }
+
+    fn intro(&self) -> Vec<String> {
+        vec![
+            i18n::plural("multi.intro", self.secrets.len() as u64, &[]),
+            i18n::text("multi.rules", &[("low", &LOW), ("high", &HIGH)]),
+            i18n::text("multi.counts_rule", &[]),
+        ]
+    }
+
+    fn prompt(&self) -> String {
+        let prompt = i18n::text(
+            "multi.prompt",
+            &[("found", &self.found()), ("count", &self.secrets.len())],
+        );
+        theme::paint(Kind::Prompt, &prompt)
+    }
+
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String> {
+        let vars = expr::Vars {
+            lo: i128::from(LOW),
+            hi: i128::from(HIGH),
+            last: self.turns.last().map(|t| i128::from(t.guess)),
+        };
+        let guess = game::parse_number(input, &vars)?;
+        if !(LOW..=HIGH).contains(&guess) {
+            return Err(i18n::text(
+                "guess.out_of_range",
+                &[("low", &LOW), ("high", &HIGH)],
+            ));
+        }
+        let mut turn = Turn {
+            guess,
+            below: 0,
+            equal: 0,
+            above: 0,
+            found: false,
+        };
+        for secret in self.secrets.iter_mut() {
+            match secret.value.cmp(&guess) {
+                Ordering::Less => turn.below += 1,
+                Ordering::Greater => turn.above += 1,
+                Ordering::Equal => {
+                    turn.equal += 1;
+                    if secret.found_on.is_none() {
+                        secret.found_on = Some(self.turns.len() + 1);
+                        turn.found = true;
+                    }
+                }
+            }
+        }
+
+        let mut lines = vec![if access::enabled() {
+            i18n::text(
+                "access.counts",
+                &[
+                    ("guess", &guess),
+                    ("below", &turn.below),
+                    ("equal", &turn.equal),
+                    ("above", &turn.above),
+                ],
+            )
+        } else {
+            i18n::text(
+                "multi.counts",
+                &[
+                    ("below", &turn.below),
+                    ("equal", &turn.equal),
+                    ("above", &turn.above),
+                ],
+            )
+        }];
+        if turn.found {
+            lines.push(i18n::text("multi.found", &[("guess", &guess)]));
+        } else if turn.equal > 0 {
+            lines.push(i18n::text("multi.already", &[("guess", &guess)]));
+        }
+        self.turns.push(turn);
+        Ok(lines)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.found() == self.secrets.len()
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        let count = self.secrets.len();
+        let mut lines = Vec::new();
+        if self.is_finished() {
+            lines.push(i18n::plural(
+                "multi.won",
+                self.turns.len() as u64,
+                &[("count", &count)],
+            ));
+        } else {
+            lines.push(i18n::text(
+                "multi.lost",
+                &[("found", &self.found()), ("count", &count)],
+            ));
+        }
+
+        lines.push(i18n::text("multi.table", &[]));
+        let found = format!("  {}", i18n::text("multi.found_marker", &[]));
+        for (i, turn) in self.turns.iter().enumerate() {
+            lines.push(format!(
+                "{:>4}  {:>5}  {:>5}  {:>5}  {:>5}{}",
+                i + 1,
+                turn.guess,
+                turn.below,
+                turn.equal,
+                turn.above,
+                if turn.found { found.as_str() } else { "" }
+            ));
+        }
+        for secret in &self.secrets {
+            lines.push(match secret.found_on {
+                Some(turn) => format!(
+                    "  {:>3} {}",
+                    secret.value,
+                    i18n::text("multi.found_on", &[("turn", &turn)])
+                ),
+                None => format!(
+                    "  {:>3} {}",
+                    secret.value,
+                    i18n::text("multi.not_found", &[])
+                ),
+            });
+        }
+        lines
+    }
+
+    fn score(&self) -> Score {
+        let found = self.found();
+        let misses = (self.turns.len() - found) as u32;
+        Score {
+            won: self.is_finished(),
+            attempts: self.turns.len(),
+            points: Some((found as u32 * SECRET_POINTS).saturating_sub(misses * MISS_COST)),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use crate::access;
+use crate::cli::Error;
+use crate::game::{self, Game, GameEvent};
+use crate::history::GameRecord;
+use crate::i18n;
+use crate::mode::{GameMode, Score, Setup};
+use crate::theme::{self, Kind};
+
+const DEFAULT_HIGH: u32 = 100;
+// Above this many candidates the explanation samples guesses instead of
+// listing every one.
+const LIST_ALL: u32 = 10;
+
+fn high_from_args(args: &[String]) -> Result<u32, String> {
+    match args {
+        [] => Ok(DEFAULT_HIGH),
+        [h] => h
+            .parse()
+            .ok()
+            .filter(|&h| h > 1)
+            .ok_or_else(|| format!("'{}' is not a number above 1", h)),
+        [_, extra, ..] => Err(format!("practice doesn't take '{}'", extra)),
+    }
+}
+
+// Guesses can be taken back and nothing is recorded, so practice games never
+// show up in stats or leaderboards.
+pub struct Practice {
+    game: Game<u32>,
+}
+
+impl Practice {
+    // practice [high]
+    pub fn new(args: &[String], setup: &Setup) -> Result<Practice, Error> {
+        let high = high_from_args(args).map_err(Error::Usage)?;
+        Ok(Practice {
+            game: Game::seeded(1u32, high, 0, setup.seed),
+        })
+    }
+}
+
+impl GameMode for Practice {
+    fn name(&self) -> String {
+        String::from("practice")
+    }
+
+    fn intro(&self) -> Vec<String> {
+        vec![
+            i18n::text("practice.intro", &[("high", &self.game.high)]),
+            i18n::text("practice.commands", &[]),
+            i18n::text("practice.not_recorded", &[]),
+        ]
+    }
+
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String> {
+        match input {
+            "undo" => {
+                let Some(earlier) = undo(&self.game) else {
+                    return Ok(vec![i18n::text("practice.nothing_to_undo", &[])]);
+                };
+                let &(guess, _) = self.game.history().last().expect("a guess to undo");
+                self.game = earlier;
+                Ok(vec![
+                    i18n::text("practice.undone", &[("guess", &guess)]),
+                    possible(&self.game),
+                ])
+            }
+            "hint" => {
+                let (lo, hi) = self.game.still_possible();
+                Ok(explain(lo, hi))
+            }
+            input => {
+                let mut lines = game::answer(&mut self.game, input)?;
+                if self.game.won() {
+                    if let Some(optimal) = self.game.optimal_attempts() {
+                        lines.push(i18n::plural(
+                            "practice.optimal",
+                            self.game.attempts() as u64,
+                            &[("optimal", &optimal)],
+                        ));
+                    }
+                } else if !access::enabled() {
+                    // the accessible sentence already says what is still possible
+                    lines.push(possible(&self.game));
+                }
+                Ok(lines)
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.game.is_finished()
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        if self.game.is_finished() {
+            return Vec::new();
+        }
+        game::give_up(&mut self.game)
+    }
+
+    fn score(&self) -> Score {
+        Score {
+            won: self.game.won(),
+            attempts: self.game.attempts(),
+            points: None,
+        }
+    }
+
+    fn record(&self, _: u64) -> Option<GameRecord> {
+        None
+    }
+}
+
+// The game as it was before the last guess, rebuilt from its events.
+fn undo(game: &Game<u32>) -> Option<Game<u32>> {
+    let events = game.events();
+    let last = events
+        .iter()
+        .rposition(|e| matches!(e, GameEvent::Guessed(_)))?;
+    Game::from_events(&events[..last])
+}
+
+fn possible(game: &Game<u32>) -> String {
+    let (lo, hi) = game.still_possible();
+    let text = if lo == hi {
+        i18n::text("practice.only", &[("secret", &lo)])
+    } else {
+        i18n::text(
+            "practice.possible",
+            &[("low", &lo), ("high", &hi), ("count", &(hi - lo + 1))],
+        )
+    };
+    theme::paint(Kind::Hint, &text)
+}
+
+// What each guess could leave: after "Too small" only the numbers above it,
+// after "Too big!" only those below, so the worst case is the bigger side.
+fn explain(lo: u32, hi: u32) -> Vec<String> {
+    let count = hi - lo + 1;
+    if count == 1 {
+        return vec![i18n::text("practice.last_one", &[("secret", &lo)])];
+    }
+    let worst = |guess: u32| (guess - lo).max(hi - guess);
+    let best = lo + (hi - lo) / 2;
+    let advice = i18n::text(
+        "practice.best",
+        &[("guess", &best), ("left", &worst(best)), ("count", &count)],
+    );
+    let mut lines = vec![theme::paint(Kind::Hint, &advice)];
+
+    let mut guesses: Vec<u32> = if count <= LIST_ALL {
+        (lo..=hi).collect()
+    } else {
+        vec![lo, lo + (hi - lo) / 4, best, hi - (hi - lo) / 4, hi]
+    };
+    guesses.dedup();
+    lines.push(i18n::text("practice.table", &[]));
+    for guess in guesses {
+        let left = worst(guess);
+        let marker = if left == worst(best) {
+            format!("  <- {}", i18n::text("practice.best_marker", &[]))
+        } else {
+            String::new()
+        };
+        lines.push(format!(
+            "{:>5}  {:>12}  {:>18}{}",
+            guess,
+            left,
+            count - left,
+            marker
+        ));
+    }
+    lines.push(i18n::text("practice.why", &[]));
+    lines
+}
@@ -0,0 +1,165 @@
+use crate::achievements::ACHIEVEMENTS;
+use crate::cli::Error;
+use crate::history::GameRecord;
+use crate::i18n;
+use crate::storage;
+use crate::wager;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+// Everything remembered about one player between runs.
+#[derive(Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    #[serde(default)]
+    pub daily: Vec<DailyResult>,
+    #[serde(default = "starting_bankroll")]
+    pub bankroll: u64,
+    #[serde(default)]
+    pub bankruptcies: u32,
+    #[serde(default)]
+    pub history: Vec<GameRecord>,
+    #[serde(default)]
+    pub achievements: Vec<Unlocked>,
+}
+
+fn starting_bankroll() -> u64 {
+    wager::STARTING_BANKROLL
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DailyResult {
+    // YYYY-MM-DD
+    pub date: String,
+    // false while the game is still going, or if it was abandoned
+    pub finished: bool,
+    pub won: bool,
+    pub share: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Unlocked {
+    pub id: String,
+    // seconds since the Unix epoch
+    pub unlocked_at: u64,
+}
+
+// The player to use when none is given: the login name, or "player".
+pub fn default_name() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .ok()
+        .filter(|name| storage::valid_name(name))
+        .unwrap_or_else(|| String::from("player"))
+}
+
+fn profiles_dir() -> PathBuf {
+    storage::data_dir().join("profiles")
+}
+
+impl Profile {
+    fn path(name: &str) -> PathBuf {
+        profiles_dir().join(format!("{}.json", name))
+    }
+
+    pub fn won(&self) -> usize {
+        self.history.iter().filter(|game| game.won).count()
+    }
+
+    // A fresh profile if this player has never played before.
+    pub fn load(name: &str) -> Result<Profile, String> {
+        if !storage::valid_name(name) {
+            return Err(format!(
+                "'{}' is not a valid player name (letters, digits, - and _ only)",
+                name
+            ));
+        }
+        let profile = storage::load(&Profile::path(name))?;
+        Ok(profile.unwrap_or_else(|| Profile {
+            name: name.to_string(),
+            daily: Vec::new(),
+            bankroll: starting_bankroll(),
+            bankruptcies: 0,
+            history: Vec::new(),
+            achievements: Vec::new(),
+        }))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        storage::save(&Profile::path(&self.name), self)
+    }
+}
+
+// Every player who has a profile, by name.
+pub fn all() -> Result<Vec<Profile>, String> {
+    let dir = profiles_dir();
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Can't read {}: {}", dir.display(), e)),
+    };
+    let mut profiles = Vec::new();
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.extension().is_some_and(|e| e == "json")
+            && let Some(profile) = storage::load::<Profile>(&path)?
+        {
+            profiles.push(profile);
+        }
+    }
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+fn show(player: &str) -> Result<(), String> {
+    let profile = Profile::load(player)?;
+    println!("{}", profile.name);
+    let games = i18n::text(
+        "profile.games",
+        &[("played", &profile.history.len()), ("won", &profile.won())],
+    );
+    println!("  {}", games);
+    let daily = i18n::text("profile.daily", &[("played", &profile.daily.len())]);
+    println!("  {}", daily);
+    let bankruptcies = i18n::plural("count.bankruptcies", u64::from(profile.bankruptcies), &[]);
+    let bankroll = i18n::text(
+        "profile.bankroll",
+        &[
+            ("bankroll", &profile.bankroll),
+            ("bankruptcies", &bankruptcies),
+        ],
+    );
+    println!("  {}", bankroll);
+    let achievements = i18n::text(
+        "profile.achievements",
+        &[
+            ("unlocked", &profile.achievements.len()),
+            ("total", &ACHIEVEMENTS.len()),
+        ],
+    );
+    println!("  {}", achievements);
+    Ok(())
+}
+
+fn list() -> Result<(), String> {
+    let profiles = all()?;
+    if profiles.is_empty() {
+        println!("{}", i18n::text("profile.nobody", &[]));
+    }
+    for profile in profiles {
+        let games = i18n::plural("count.games", profile.history.len() as u64, &[]);
+        println!("  {:<20} {}", profile.name, games);
+    }
+    Ok(())
+}
+
+// profile [list]
+pub fn run(args: &[String], player: &str) -> Result<(), Error> {
+    match args.first().map(String::as_str) {
+        None => Ok(show(player)?),
+        Some("list") if args.len() == 1 => Ok(list()?),
+        _ => Err(Error::Usage(String::from("Usage: profile [list]"))),
+    }
+}
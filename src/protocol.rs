@@ -0,0 +1,267 @@
+// The --protocol mode: one JSON object per line each way, for bots and test
+// harnesses. PROTOCOL.md describes every message.
+use crate::game::{Game, GameEvent};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead};
+
+pub const VERSION: u32 = 1;
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    NewGame {
+        #[serde(default = "default_low")]
+        low: i64,
+        #[serde(default = "default_high")]
+        high: i64,
+        #[serde(default)]
+        max_attempts: Option<usize>,
+        // the same seed always gives the same secret
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+    Guess {
+        value: i64,
+    },
+    Quit,
+}
+
+fn default_low() -> i64 {
+    1
+}
+
+fn default_high() -> i64 {
+    100
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Reply {
+    Ready {
+        version: u32,
+    },
+    Started {
+        low: i64,
+        high: i64,
+        max_attempts: Option<usize>,
+    },
+    Feedback {
+        guess: i64,
+        // "too_small", "too_big" or "correct"
+        result: &'static str,
+        attempts: usize,
+    },
+    GameOver {
+        won: bool,
+        attempts: usize,
+        secret: i64,
+    },
+    Error {
+        message: String,
+    },
+}
+
+pub fn encode(reply: &Reply) -> String {
+    serde_json::to_string(reply).expect("replies always serialize")
+}
+
+fn send(reply: &Reply) {
+    println!("{}", encode(reply));
+}
+
+// The replies for a game event; a guess is reported along with its result.
+pub fn replies(game: &Game<i64>, event: &GameEvent<i64>) -> Vec<Reply> {
+    let feedback = |result| Reply::Feedback {
+        guess: game.history().last().map_or(0, |&(guess, _)| guess),
+        result,
+        attempts: game.attempts(),
+    };
+    let game_over = |won| Reply::GameOver {
+        won,
+        attempts: game.attempts(),
+        secret: game.secret(),
+    };
+    match *event {
+        GameEvent::Started {
+            low,
+            high,
+            max_attempts,
+            ..
+        } => vec![Reply::Started {
+            low,
+            high,
+            max_attempts,
+        }],
+        GameEvent::Guessed(_) => Vec::new(),
+        GameEvent::HintGiven(result) if result.is_lt() => vec![feedback("too_small")],
+        GameEvent::HintGiven(_) => vec![feedback("too_big")],
+        GameEvent::Won => vec![feedback("correct"), game_over(true)],
+        GameEvent::Lost | GameEvent::Abandoned => vec![game_over(false)],
+    }
+}
+
+pub fn run() {
+    send(&Reply::Ready { version: VERSION });
+
+    let mut game: Option<Game<i64>> = None;
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let replies = match serde_json::from_str(&line) {
+            Ok(Request::Quit) => break,
+            Ok(request) => respond(&mut game, request),
+            Err(e) => vec![Reply::Error {
+                message: format!("Bad request: {}", e),
+            }],
+        };
+        for reply in replies {
+            send(&reply);
+        }
+    }
+
+    if let Some(current) = game.as_mut() {
+        let seen = current.events().len();
+        current.abandon();
+        for reply in since(current, seen) {
+            send(&reply);
+        }
+    }
+}
+
+// What to send back for a new_game or a guess.
+fn respond(game: &mut Option<Game<i64>>, request: Request) -> Vec<Reply> {
+    let error = |message: String| vec![Reply::Error { message }];
+    match request {
+        Request::NewGame {
+            low,
+            high,
+            max_attempts,
+            seed,
+        } => {
+            if low >= high {
+                return error(String::from("low must be below high"));
+            }
+            let mut replies = Vec::new();
+            if let Some(old) = game.as_mut() {
+                let seen = old.events().len();
+                old.abandon();
+                replies.extend(since(old, seen));
+            }
+            let started = match seed {
+                Some(seed) => Game::seeded(low, high, 0, seed),
+                None => Game::new(low, high, 0),
+            };
+            let started = game.insert(started.with_max_attempts(max_attempts));
+            replies.extend(since(started, 0));
+            replies
+        }
+        Request::Guess { value } => match game.as_mut() {
+            Some(current) if !current.is_finished() => {
+                if value < current.low || value > current.high {
+                    return error(format!(
+                        "Guesses must be between {} and {}",
+                        current.low, current.high
+                    ));
+                }
+                let seen = current.events().len();
+                current.guess(value);
+                since(current, seen)
+            }
+            _ => error(String::from("No game in progress; send new_game first")),
+        },
+        Request::Quit => Vec::new(),
+    }
+}
+
+// The replies for every event after the first `seen`.
+fn since(game: &Game<i64>, seen: usize) -> Vec<Reply> {
+    game.events()[seen..]
+        .iter()
+        .flat_map(|event| replies(game, event))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Plays `requests` (one JSON object each) and returns the replies as
+    // they would be sent.
+    fn session(requests: &[&str]) -> Vec<String> {
+        let mut game = None;
+        requests
+            .iter()
+            .flat_map(|line| respond(&mut game, serde_json::from_str(line).unwrap()))
+            .map(|reply| encode(&reply))
+            .collect()
+    }
+
+    #[test]
+    fn replies_follow_the_game() {
+        // seed 7 hides 25 in 1..=100
+        let replies = session(&[
+            r#"{"type":"new_game","seed":7}"#,
+            r#"{"type":"guess","value":50}"#,
+            r#"{"type":"guess","value":12}"#,
+            r#"{"type":"guess","value":25}"#,
+        ]);
+        assert_eq!(
+            replies,
+            [
+                r#"{"type":"started","low":1,"high":100,"max_attempts":null}"#,
+                r#"{"type":"feedback","guess":50,"result":"too_big","attempts":1}"#,
+                r#"{"type":"feedback","guess":12,"result":"too_small","attempts":2}"#,
+                r#"{"type":"feedback","guess":25,"result":"correct","attempts":3}"#,
+                r#"{"type":"game_over","won":true,"attempts":3,"secret":25}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn a_lost_or_replaced_game_is_over() {
+        let replies = session(&[
+            r#"{"type":"new_game","seed":7,"max_attempts":1}"#,
+            r#"{"type":"guess","value":90}"#,
+            r#"{"type":"guess","value":25}"#,
+            r#"{"type":"new_game","low":1,"high":10,"seed":7}"#,
+            r#"{"type":"new_game","seed":7}"#,
+        ]);
+        assert_eq!(
+            replies,
+            [
+                r#"{"type":"started","low":1,"high":100,"max_attempts":1}"#,
+                r#"{"type":"feedback","guess":90,"result":"too_big","attempts":1}"#,
+                r#"{"type":"game_over","won":false,"attempts":1,"secret":25}"#,
+                r#"{"type":"error","message":"No game in progress; send new_game first"}"#,
+                r#"{"type":"started","low":1,"high":10,"max_attempts":null}"#,
+                r#"{"type":"game_over","won":false,"attempts":0,"secret":5}"#,
+                r#"{"type":"started","low":1,"high":100,"max_attempts":null}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_requests_are_errors() {
+        let replies = session(&[
+            r#"{"type":"guess","value":5}"#,
+            r#"{"type":"new_game","low":10,"high":10}"#,
+            r#"{"type":"new_game","seed":7}"#,
+            r#"{"type":"guess","value":0}"#,
+            r#"{"type":"guess","value":101}"#,
+        ]);
+        assert_eq!(
+            replies,
+            [
+                r#"{"type":"error","message":"No game in progress; send new_game first"}"#,
+                r#"{"type":"error","message":"low must be below high"}"#,
+                r#"{"type":"started","low":1,"high":100,"max_attempts":null}"#,
+                r#"{"type":"error","message":"Guesses must be between 1 and 100"}"#,
+                r#"{"type":"error","message":"Guesses must be between 1 and 100"}"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use crate::cli::{self, Error};
+use crate::game::{self, Decimal, Game, GameEvent, Number};
+use crate::history::{self, GameRecord};
+use crate::i18n;
+use crate::mode::{self, GameMode, Score};
+use crate::storage;
+use crate::timer;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Everything needed to play a game again: the seed rebuilds the secret and
+// the inputs are fed back through the same game loop.
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    pub mode: String,
+    // Number::KIND of the secret
+    pub kind: String,
+    // numbers are kept as text so u128 and decimals survive the trip
+    pub low: String,
+    pub high: String,
+    pub tolerance: String,
+    pub max_attempts: Option<usize>,
+    pub seed: u64,
+    // lines printed before the first prompt
+    pub intro: Vec<String>,
+    // every line typed, invalid ones included
+    pub inputs: Vec<Input>,
+    // the answers given, in order, to check playback against
+    pub hints: Vec<String>,
+    pub finished_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Input {
+    // since the first prompt
+    pub at_ms: u64,
+    pub line: String,
+}
+
+fn replays_dir() -> PathBuf {
+    storage::data_dir().join("replays")
+}
+
+// The answers a game gave, in order.
+fn hints<T: Number>(game: &Game<T>) -> Vec<&'static str> {
+    game.events()
+        .iter()
+        .filter_map(|event| match event {
+            GameEvent::HintGiven(Ordering::Less) => Some("Too small"),
+            GameEvent::HintGiven(_) => Some("Too big!"),
+            GameEvent::Won => Some("You Win!"),
+            _ => None,
+        })
+        .collect()
+}
+
+impl Replay {
+    // An empty recording of `game`, which hasn't been played yet.
+    pub fn start<T: Number>(mode: &str, intro: &[String], game: &Game<T>) -> Replay {
+        Replay {
+            mode: mode.to_string(),
+            kind: T::KIND.to_string(),
+            low: game.low.to_string(),
+            high: game.high.to_string(),
+            tolerance: game.tolerance.to_string(),
+            max_attempts: game.max_attempts(),
+            seed: game.seed,
+            intro: intro.to_vec(),
+            inputs: Vec::new(),
+            hints: Vec::new(),
+            finished_at: 0,
+        }
+    }
+
+    // The game as it was before the first input.
+    fn new_game<T: Number>(&self) -> Result<Game<T>, String> {
+        Ok(Game::seeded(
+            T::parse(&self.low)?,
+            T::parse(&self.high)?,
+            T::parse(&self.tolerance)?,
+            self.seed,
+        )
+        .with_max_attempts(self.max_attempts))
+    }
+
+    // The game as recorded so far, with its inputs applied quietly.
+    pub fn rebuild<T: Number>(&self) -> Result<Game<T>, String> {
+        let mut game = self.new_game()?;
+        for input in &self.inputs {
+            if let Ok(guess) = game.parse_guess(input.line.trim())
+                && guess >= game.low
+                && guess <= game.high
+                && !game.is_finished()
+            {
+                game.guess(guess);
+            }
+        }
+        Ok(game)
+    }
+
+    // Saves the recording of the now finished game, if anything was typed,
+    // and returns where it went.
+    pub fn finish<T: Number>(&mut self, game: &Game<T>) -> Vec<String> {
+        if self.inputs.is_empty() {
+            return Vec::new();
+        }
+        self.hints = hints(game).into_iter().map(String::from).collect();
+        self.finished_at = history::now();
+        let mut millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        // two replays finished in the same millisecond, e.g. a tournament match
+        while replays_dir()
+            .join(format!("{}-{}.json", millis, self.mode))
+            .exists()
+        {
+            millis += 1;
+        }
+        let path = replays_dir().join(format!("{}-{}.json", millis, self.mode));
+        match storage::save(&path, self) {
+            Ok(()) if cli::quiet() => Vec::new(),
+            Ok(()) => vec![i18n::text("replay.saved", &[("path", &path.display())])],
+            Err(message) => vec![i18n::text("replay.save_failed", &[("error", &message)])],
+        }
+    }
+}
+
+// A game played through mode::play with every input recorded. Nothing is
+// saved along the way; `keep` writes the replay once the caller is ready,
+// e.g. after the other player of a match has had their turn too.
+pub struct Recording<T> {
+    pub game: Game<T>,
+    pub replay: Replay,
+    start: Instant,
+}
+
+impl<T: Number> Recording<T> {
+    pub fn new(mode: &str, intro: &[String], game: Game<T>) -> Recording<T> {
+        Recording {
+            replay: Replay::start(mode, intro, &game),
+            game,
+            start: Instant::now(),
+        }
+    }
+
+    // Saves the replay; the lines say where it went.
+    pub fn keep(&mut self) -> Vec<String> {
+        self.replay.finish(&self.game)
+    }
+}
+
+impl<T: Number> GameMode for Recording<T> {
+    fn name(&self) -> String {
+        self.replay.mode.clone()
+    }
+
+    fn intro(&self) -> Vec<String> {
+        self.replay.intro.clone()
+    }
+
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String> {
+        self.replay.inputs.push(Input {
+            at_ms: self.start.elapsed().as_millis() as u64,
+            line: input.to_string(),
+        });
+        game::answer(&mut self.game, input)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.game.is_finished()
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        if self.game.is_finished() {
+            Vec::new()
+        } else {
+            game::give_up(&mut self.game)
+        }
+    }
+
+    fn score(&self) -> Score {
+        Score {
+            won: self.game.won(),
+            attempts: self.game.attempts(),
+            points: None,
+        }
+    }
+
+    // The caller decides what the game counts for.
+    fn record(&self, _: u64) -> Option<GameRecord> {
+        None
+    }
+}
+
+fn playback<T: Number>(replay: &Replay, speed: f64) -> Result<(), String> {
+    let mut playing = Recording::new(&replay.mode, &replay.intro, replay.new_game::<T>()?);
+    let start = Instant::now();
+    let mut inputs = replay.inputs.iter();
+    mode::play_from(
+        &mut playing,
+        |_| {
+            let Some(input) = inputs.next() else {
+                return timer::Input::Closed;
+            };
+            // a speed so slow the delay overflows plays without delay
+            let due = Duration::try_from_secs_f64(input.at_ms as f64 / 1000.0 / speed);
+            if let (true, Ok(due)) = (speed > 0.0, due) {
+                thread::sleep(due.saturating_sub(start.elapsed()));
+            }
+            // stand in for the terminal echoing what was typed
+            println!("{}", input.line);
+            timer::Input::Line(input.line.clone())
+        },
+        &mut io::stdout(),
+    );
+
+    if hints(&playing.game) != replay.hints {
+        return Err(i18n::text("replay.mismatch", &[]));
+    }
+    Ok(())
+}
+
+// A path, or the name of a file in the replays folder.
+fn find(name: &str) -> PathBuf {
+    let path = Path::new(name);
+    if path.exists() {
+        return path.to_path_buf();
+    }
+    let in_dir = replays_dir().join(name);
+    if in_dir.extension().is_none() {
+        in_dir.with_extension("json")
+    } else {
+        in_dir
+    }
+}
+
+fn list() -> Result<(), String> {
+    let mut names: Vec<String> = match fs::read_dir(replays_dir()) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .filter(|n| n.ends_with(".json"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    if names.is_empty() {
+        println!("{}", i18n::text("replay.none", &[]));
+        return Ok(());
+    }
+    names.sort();
+    println!(
+        "{}",
+        i18n::text("replay.list", &[("path", &replays_dir().display())])
+    );
+    for name in names.iter().rev().take(20) {
+        println!("  {}", name);
+    }
+    Ok(())
+}
+
+// replay [<file> [speed]]: speed 2 is twice as fast, 0 as fast as possible
+pub fn run(args: &[String]) -> Result<(), Error> {
+    let Some(name) = args.first() else {
+        return Ok(list()?);
+    };
+    if let Some(extra) = args.get(2) {
+        return Err(Error::Usage(format!("replay doesn't take '{}'", extra)));
+    }
+    let speed: f64 = match args.get(1) {
+        Some(s) => s
+            .parse()
+            .ok()
+            .filter(|s: &f64| *s >= 0.0)
+            .ok_or_else(|| Error::Usage(format!("'{}' is not a speed", s)))?,
+        None => 1.0,
+    };
+    let path = find(name);
+    let replay: Replay = storage::load(&path)?
+        .ok_or_else(|| i18n::text("replay.not_found", &[("path", &path.display())]))?;
+
+    let played = match replay.kind.as_str() {
+        "u32" => playback::<u32>(&replay, speed),
+        "i64" => playback::<i64>(&replay, speed),
+        "u128" => playback::<u128>(&replay, speed),
+        "decimal" => playback::<Decimal>(&replay, speed),
+        kind => Err(i18n::text("replay.unknown_kind", &[("kind", &kind)])),
+    };
+    Ok(played?)
+}
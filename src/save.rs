@@ -0,0 +1,82 @@
+use crate::classic::Classic;
+use crate::game::{Decimal, Difficulty, Number};
+use crate::i18n;
+use crate::mode;
+use crate::replay::Replay;
+use crate::storage;
+use crate::timer::{Input, TimedInput};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+// An unfinished game. Its replay so far is enough to rebuild it exactly:
+// the seed gives the secret back and the inputs the guesses.
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    pub player: String,
+    pub difficulty: Option<Difficulty>,
+    // time played before this sitting
+    pub millis: u64,
+    pub replay: Replay,
+}
+
+// One saved game per player; starting a new one replaces it, once the
+// player agrees to.
+fn path(player: &str) -> PathBuf {
+    storage::data_dir()
+        .join("saves")
+        .join(format!("{}.sav", player))
+}
+
+// Keeps `saved` to be resumed later, replacing any earlier save.
+pub fn store(saved: &SavedGame) -> Result<(), String> {
+    storage::save_scrambled(&path(&saved.player), saved)
+}
+
+// Before a new game replaces `player`'s save: true if there's none, or the
+// player says to go ahead.
+pub fn may_replace(player: &str) -> bool {
+    if !path(player).exists() {
+        return true;
+    }
+    println!("{}", i18n::text("save.replace", &[("player", &player)]));
+    let keyboard = TimedInput::new();
+    match keyboard.read_line(None, String::new) {
+        // yes, ja, oui
+        Input::Line(answer) if answer.trim().to_lowercase().starts_with(['y', 'j', 'o']) => true,
+        _ => {
+            println!("{}", i18n::text("save.kept", &[]));
+            false
+        }
+    }
+}
+
+// The game is over, so there's nothing left to resume.
+pub fn remove(player: &str) {
+    let _ = fs::remove_file(path(player));
+}
+
+pub fn resume(player: &str) -> Result<(), String> {
+    if !storage::valid_name(player) {
+        return Err(format!("'{}' is not a valid player name", player));
+    }
+    let saved: SavedGame = storage::load_scrambled(&path(player))?
+        .ok_or_else(|| i18n::text("resume.none", &[("player", &player)]))?;
+    match saved.replay.kind.as_str() {
+        "u32" => resume_as::<u32>(saved),
+        "i64" => resume_as::<i64>(saved),
+        "u128" => resume_as::<u128>(saved),
+        "decimal" => resume_as::<Decimal>(saved),
+        kind => Err(format!("Unknown number kind '{}' in saved game", kind)),
+    }
+}
+
+fn resume_as<T: Number + 'static>(saved: SavedGame) -> Result<(), String> {
+    let game = saved.replay.rebuild::<T>()?;
+    if game.is_finished() {
+        remove(&saved.player);
+        return Err(i18n::text("resume.over", &[]));
+    }
+    let player = saved.player.clone();
+    mode::play(&mut Classic::resumed(saved, game), Some(&player), None)
+}
@@ -0,0 +1,232 @@
+// House rules written in Rhai. A script defines some of these functions:
+//
+//   secret()                        required; picks the secret, any value
+//   judge(guess, secret)            required; 0 wins, below 0 is "Too small",
+//                                   above 0 is "Too big!"
+//   feedback(guess, secret, verdict, tries)
+//                                   the lines to print instead of the usual
+//                                   hint; a string or an array of strings
+//   intro()                         the same, before the first guess
+//   max_attempts()                  the player loses after this many
+//   score(tries)                    points for a win
+//
+// Guesses are whole numbers. `rand(low, high)` gives a random number in
+// low..=high. Scripts can't import modules or call eval, and are stopped if
+// they run too long or build anything too big.
+use crate::cli::Error;
+use crate::game;
+use crate::i18n;
+use crate::mode::{GameMode, Score, Setup};
+use rand::Rng;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, Array, CallFnOptions, Dynamic, Engine, FuncArgs, Scope};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::fs;
+use std::rc::Rc;
+
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_SIZE: usize = 10_000;
+
+pub struct ScriptMode {
+    engine: Engine,
+    ast: AST,
+    secret: Dynamic,
+    max_attempts: Option<usize>,
+    guesses: Vec<i64>,
+    verdict: Option<Ordering>,
+    // a script error ends the game
+    failed: Option<String>,
+}
+
+fn sandbox(seed: u64) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH)
+        .set_max_string_size(MAX_SIZE)
+        .set_max_array_size(MAX_SIZE)
+        .set_max_map_size(MAX_SIZE)
+        .set_module_resolver(DummyModuleResolver::new())
+        .disable_symbol("eval");
+    let rng = Rc::new(RefCell::new(game::seeded_rng(seed)));
+    engine.register_fn("rand", move |low: i64, high: i64| {
+        if low >= high {
+            low
+        } else {
+            rng.borrow_mut().gen_range(low, high.saturating_add(1))
+        }
+    });
+    engine
+}
+
+fn failed(message: &str) -> String {
+    i18n::text("script.failed", &[("error", &message)])
+}
+
+// Lines from a script: one string, or an array of them.
+fn lines(value: Dynamic) -> Vec<String> {
+    if value.is_array() {
+        value
+            .cast::<Array>()
+            .into_iter()
+            .map(|line| line.to_string())
+            .collect()
+    } else if value.is_unit() {
+        Vec::new()
+    } else {
+        vec![value.to_string()]
+    }
+}
+
+impl ScriptMode {
+    // script <file.rhai>
+    pub fn new(args: &[String], setup: &Setup) -> Result<ScriptMode, Error> {
+        let [path] = args else {
+            return Err(Error::Usage(String::from("Usage: play script <file.rhai>")));
+        };
+        let source = fs::read_to_string(path).map_err(|e| format!("Can't read {}: {}", path, e))?;
+        let engine = sandbox(setup.seed);
+        let ast = engine
+            .compile(&source)
+            .map_err(|e| format!("{}: {}", path, e))?;
+
+        let mut mode = ScriptMode {
+            engine,
+            ast,
+            secret: Dynamic::UNIT,
+            max_attempts: None,
+            guesses: Vec::new(),
+            verdict: None,
+            failed: None,
+        };
+        for name in ["secret", "judge"] {
+            if !mode.defines(name) {
+                return Err(Error::Failed(format!(
+                    "{} has no {}() function",
+                    path, name
+                )));
+            }
+        }
+        mode.secret = mode.call("secret", ())?;
+        if mode.defines("max_attempts") {
+            let max = mode.call_int("max_attempts", ())?;
+            mode.max_attempts = Some(max.max(1) as usize);
+        }
+        Ok(mode)
+    }
+
+    fn defines(&self, name: &str) -> bool {
+        self.ast.iter_functions().any(|f| f.name == name)
+    }
+
+    fn call(&self, name: &str, args: impl FuncArgs) -> Result<Dynamic, String> {
+        // only the functions run, never the script's top level
+        let options = CallFnOptions::new().eval_ast(false);
+        self.engine
+            .call_fn_with_options(options, &mut Scope::new(), &self.ast, name, args)
+            .map_err(|e| format!("{}(): {}", name, e))
+    }
+
+    fn call_int(&self, name: &str, args: impl FuncArgs) -> Result<i64, String> {
+        self.call(name, args)?
+            .as_int()
+            .map_err(|got| format!("{}() gave back {} instead of a number", name, got))
+    }
+
+    fn out_of_attempts(&self) -> bool {
+        self.max_attempts
+            .is_some_and(|max| self.guesses.len() >= max)
+    }
+}
+
+impl GameMode for ScriptMode {
+    fn name(&self) -> String {
+        String::from("script")
+    }
+
+    fn intro(&self) -> Vec<String> {
+        if self.defines("intro") {
+            match self.call("intro", ()) {
+                Ok(value) => return lines(value),
+                Err(message) => return vec![failed(&message)],
+            }
+        }
+        let mut intro = vec![i18n::text("script.intro", &[])];
+        if let Some(max) = self.max_attempts {
+            intro.push(i18n::plural("intro.attempts", max as u64, &[]));
+        }
+        intro
+    }
+
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String> {
+        let guess: i64 = input
+            .parse()
+            .map_err(|_| i18n::text("guess.not_a_number", &[]))?;
+        self.guesses.push(guess);
+
+        let verdict = match self.call_int("judge", (guess, self.secret.clone())) {
+            Ok(verdict) => verdict.cmp(&0),
+            Err(message) => {
+                self.failed = Some(message.clone());
+                return Ok(vec![failed(&message)]);
+            }
+        };
+        self.verdict = Some(verdict);
+
+        let mut text = vec![i18n::text("guess.echo", &[("guess", &guess)])];
+        if self.defines("feedback") {
+            let tries = self.guesses.len() as i64;
+            let args = (guess, self.secret.clone(), verdict as i64, tries);
+            match self.call("feedback", args) {
+                Ok(value) => text.extend(lines(value)),
+                Err(message) => {
+                    self.failed = Some(message.clone());
+                    text.push(failed(&message));
+                }
+            }
+        } else {
+            text.push(match verdict {
+                Ordering::Equal => game::won(),
+                _ => game::hint(verdict),
+            });
+        }
+        Ok(text)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.failed.is_some() || self.verdict == Some(Ordering::Equal) || self.out_of_attempts()
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        if self.verdict == Some(Ordering::Equal) {
+            return Vec::new();
+        }
+        let mut lines = Vec::new();
+        if self.failed.is_none() && self.out_of_attempts() {
+            lines.push(i18n::text("end.lost", &[("secret", &self.secret)]));
+        } else {
+            lines.push(i18n::text("end.reveal", &[("secret", &self.secret)]));
+        }
+        lines
+    }
+
+    fn score(&self) -> Score {
+        let won = self.verdict == Some(Ordering::Equal);
+        let points = if won && self.defines("score") {
+            self.call_int("score", (self.guesses.len() as i64,))
+                .ok()
+                .map(|p| p.clamp(0, i64::from(u32::MAX)) as u32)
+        } else {
+            None
+        };
+        Score {
+            won,
+            attempts: self.guesses.len(),
+            points,
+        }
+    }
+}
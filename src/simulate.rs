@@ -0,0 +1,133 @@
+// A strategy plays many games on its own, to see how it does on average and
+// at worst. The same seed always gives the same games.
+use crate::game::{self, Game};
+use crate::i18n;
+use rand::Rng;
+
+const MAX_HIGH: u32 = 1_000_000;
+
+#[derive(Clone, Copy)]
+enum Strategy {
+    // the middle of what's still possible
+    Binary,
+    // anything still possible
+    Random,
+    // the lowest number still possible
+    Linear,
+}
+
+impl Strategy {
+    fn from_name(name: &str) -> Option<Strategy> {
+        match name {
+            "binary" => Some(Strategy::Binary),
+            "random" => Some(Strategy::Random),
+            "linear" => Some(Strategy::Linear),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Strategy::Binary => "binary",
+            Strategy::Random => "random",
+            Strategy::Linear => "linear",
+        }
+    }
+
+    fn next_guess<R: Rng>(self, game: &Game<u32>, rng: &mut R) -> u32 {
+        let (low, high) = game.still_possible();
+        match self {
+            Strategy::Binary => low + (high - low) / 2,
+            Strategy::Random => rng.gen_range(low, high + 1),
+            Strategy::Linear => low,
+        }
+    }
+}
+
+pub struct Settings {
+    games: u64,
+    high: u32,
+    strategy: Strategy,
+    seed: u64,
+}
+
+impl Settings {
+    // simulate [--games N] [--high N] [--strategy binary|random|linear]
+    pub fn from_args(args: &[String], seed: Option<u64>) -> Result<Settings, String> {
+        let mut settings = Settings {
+            games: 1000,
+            high: 100,
+            strategy: Strategy::Binary,
+            seed: seed.unwrap_or_else(rand::random),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--games" => {
+                    settings.games = args
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|&n| n > 0)
+                        .ok_or("--games needs a number above 0")?;
+                }
+                "--high" => {
+                    settings.high = args
+                        .next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|n| (2..=MAX_HIGH).contains(n))
+                        .ok_or_else(|| format!("--high needs a number from 2 to {}", MAX_HIGH))?;
+                }
+                "--strategy" => {
+                    settings.strategy = args
+                        .next()
+                        .and_then(|name| Strategy::from_name(name))
+                        .ok_or("--strategy is binary, random or linear")?;
+                }
+                _ => return Err(format!("Unexpected '{}'", arg)),
+            }
+        }
+        Ok(settings)
+    }
+}
+
+pub fn run(settings: Settings) {
+    let mut rng = game::seeded_rng(settings.seed);
+    let mut attempts = Vec::new();
+    for i in 0..settings.games {
+        let mut game = Game::seeded(1, settings.high, 0, settings.seed.wrapping_add(i));
+        while !game.is_finished() {
+            let guess = settings.strategy.next_guess(&game, &mut rng);
+            game.guess(guess);
+        }
+        attempts.push(game.attempts());
+    }
+
+    let games = i18n::plural("count.games", settings.games, &[]);
+    let summary = i18n::text(
+        "simulate.summary",
+        &[
+            ("games", &games),
+            ("high", &settings.high),
+            ("strategy", &settings.strategy.name()),
+            ("seed", &settings.seed),
+        ],
+    );
+    println!("{}", summary);
+    let total: usize = attempts.iter().sum();
+    let average = format!("{:.2}", total as f64 / attempts.len() as f64);
+    let spread = i18n::text(
+        "simulate.attempts",
+        &[
+            ("average", &average),
+            ("best", attempts.iter().min().unwrap_or(&0)),
+            ("worst", attempts.iter().max().unwrap_or(&0)),
+        ],
+    );
+    println!("{}", spread);
+    if let Some(optimal) = Game::new(1, settings.high, 0).optimal_attempts() {
+        println!(
+            "{}",
+            i18n::text("simulate.optimal", &[("optimal", &optimal)])
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// Solver for Ulam's game: find a number in low..=high when up to `max_lies`
+// of the "Too small"/"Too big!" answers may be lies. "You Win!" is never a lie.
+//
+// Every candidate carries the number of lies it would take for it to be the
+// secret; once that goes past `max_lies` the candidate is ruled out. Guesses
+// follow Berlekamp's volume argument: pick the guess whose worst answer leaves
+// the smallest total volume.
+#[derive(Clone, Debug)]
+pub struct LieSolver {
+    low: u32,
+    max_lies: u32,
+    // lies[i] is the lie count for candidate low + i, None once ruled out
+    lies: Vec<Option<u32>>,
+}
+
+impl LieSolver {
+    pub fn new(low: u32, high: u32, max_lies: u32) -> LieSolver {
+        LieSolver {
+            low,
+            max_lies,
+            lies: vec![Some(0); (high - low + 1) as usize],
+        }
+    }
+
+    pub fn candidates(&self) -> impl Iterator<Item = u32> + '_ {
+        self.lies
+            .iter()
+            .enumerate()
+            .filter(|(_, lies)| lies.is_some())
+            .map(move |(i, _)| self.low + i as u32)
+    }
+
+    // Narrow the candidates after `guess` got `answer` (guess.cmp(&secret)).
+    pub fn record(&mut self, guess: u32, answer: Ordering) {
+        for (i, slot) in self.lies.iter_mut().enumerate() {
+            let Some(lies) = *slot else { continue };
+            let candidate = self.low + i as u32;
+            *slot = match (answer, guess.cmp(&candidate)) {
+                (Ordering::Equal, Ordering::Equal) => Some(lies),
+                (Ordering::Equal, _) => None,
+                // the game would have said "You Win!"
+                (_, Ordering::Equal) => None,
+                (reported, actual) if reported == actual => Some(lies),
+                _ if lies < self.max_lies => Some(lies + 1),
+                _ => None,
+            };
+        }
+    }
+
+    pub fn next_guess(&self) -> u32 {
+        let alive: Vec<(usize, u32)> = self
+            .lies
+            .iter()
+            .enumerate()
+            .filter_map(|(i, lies)| lies.map(|l| (i, l)))
+            .collect();
+        let (first, last) = (alive[0].0, alive[alive.len() - 1].0);
+        if first == last {
+            return self.low + first as u32;
+        }
+
+        let q = self.questions_needed().max(1) - 1;
+        let mut honest = vec![0u128; self.lies.len()];
+        let mut lied = vec![0u128; self.lies.len()];
+        for &(i, lies) in &alive {
+            honest[i] = volume(q, self.max_lies - lies);
+            if lies < self.max_lies {
+                lied[i] = volume(q, self.max_lies - lies - 1);
+            }
+        }
+
+        // For a guess at index i, "Too small" keeps the candidates above i
+        // honestly and charges a lie to those below; "Too big!" the reverse.
+        let total_honest: u128 = honest.iter().sum();
+        let total_lied: u128 = lied.iter().sum();
+        let (mut honest_below, mut lied_below) = (0u128, 0u128);
+        let mut best = (u128::MAX, false, first);
+        for i in first..=last {
+            let honest_above = total_honest - honest_below - honest[i];
+            let lied_above = total_lied - lied_below - lied[i];
+            let too_small = honest_above + lied_below;
+            let too_big = honest_below + lied_above;
+
+            let worst = too_small.max(too_big);
+            let is_candidate = self.lies[i].is_some();
+            if worst < best.0 || (worst == best.0 && is_candidate && !best.1) {
+                best = (worst, is_candidate, i);
+            }
+            honest_below += honest[i];
+            lied_below += lied[i];
+        }
+        self.low + best.2 as u32
+    }
+
+    // Berlekamp's lower bound: the fewest questions that could possibly
+    // separate the remaining candidates.
+    pub fn questions_needed(&self) -> u32 {
+        let mut q = 0;
+        loop {
+            let total: u128 = self
+                .lies
+                .iter()
+                .flatten()
+                .map(|&lies| volume(q, self.max_lies - lies))
+                .sum();
+            if total <= 1u128 << q {
+                return q;
+            }
+            q += 1;
+        }
+    }
+
+    fn key(&self) -> Vec<u8> {
+        self.lies
+            .iter()
+            .map(|lies| lies.map_or(u8::MAX, |l| l as u8))
+            .collect()
+    }
+}
+
+// Number of answer patterns of length q containing at most `lies` lies.
+fn volume(q: u32, lies: u32) -> u128 {
+    let mut total = 0u128;
+    let mut binomial = 1u128;
+    for i in 0..=lies.min(q) {
+        total += binomial;
+        binomial = binomial * u128::from(q - i) / u128::from(i + 1);
+    }
+    total
+}
+
+// Plays the solver against every possible sequence of answers and returns
+// the most attempts it can ever need. Because each sequence of answers that
+// stays within the lie budget is some secret and some choice of lies, this
+// is a proven bound for the game, not an estimate.
+pub fn worst_case(low: u32, high: u32, max_lies: u32) -> u32 {
+    let mut seen = HashMap::new();
+    worst_from(&LieSolver::new(low, high, max_lies), &mut seen)
+}
+
+fn worst_from(solver: &LieSolver, seen: &mut HashMap<Vec<u8>, u32>) -> u32 {
+    if let Some(&known) = seen.get(&solver.key()) {
+        return known;
+    }
+
+    let guess = solver.next_guess();
+    let mut worst = 0;
+    if solver.candidates().any(|c| c == guess) {
+        worst = 1;
+    }
+    for answer in [Ordering::Less, Ordering::Greater] {
+        let mut next = solver.clone();
+        next.record(guess, answer);
+        if next.candidates().next().is_some() {
+            worst = worst.max(1 + worst_from(&next, seen));
+        }
+    }
+
+    seen.insert(solver.key(), worst);
+    worst
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::liar::MAX_LIES;
+
+    // Plays the solver for `secret` against an adversary that may lie up to
+    // `lies_left` more times, every way it can. Returns the most guesses the
+    // solver took to hit the secret; panics if the secret is ever ruled out.
+    fn longest_win(solver: &LieSolver, secret: u32, lies_left: u32, limit: u32) -> u32 {
+        assert!(limit > 0, "out of attempts before finding {}", secret);
+        let guess = solver.next_guess();
+        let truth = guess.cmp(&secret);
+        if truth == Ordering::Equal {
+            return 1;
+        }
+        let mut answers = vec![(truth, lies_left)];
+        if lies_left > 0 {
+            answers.push((truth.reverse(), lies_left - 1));
+        }
+        let mut longest = 0;
+        for (answer, lies_left) in answers {
+            let mut next = solver.clone();
+            next.record(guess, answer);
+            assert!(
+                next.candidates().any(|c| c == secret),
+                "{} ruled out",
+                secret
+            );
+            longest = longest.max(1 + longest_win(&next, secret, lies_left, limit - 1));
+        }
+        longest
+    }
+
+    #[test]
+    fn always_wins_within_the_worst_case() {
+        for max_lies in 0..=MAX_LIES {
+            for high in 1..=16 {
+                let bound = worst_case(1, high, max_lies);
+                let solver = LieSolver::new(1, high, max_lies);
+                let longest = (1..=high)
+                    .map(|secret| longest_win(&solver, secret, max_lies, bound))
+                    .max()
+                    .unwrap();
+                // the bound is reached, so it is no looser than it has to be
+                assert_eq!(longest, bound, "1..={} with {} lies", high, max_lies);
+            }
+        }
+    }
+
+    #[test]
+    fn no_lies_is_binary_search() {
+        for high in 1..=64u32 {
+            assert_eq!(worst_case(1, high, 0), u32::BITS - high.leading_zeros());
+        }
+    }
+}
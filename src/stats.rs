@@ -0,0 +1,307 @@
+// How a player has done so far, from the games in their profile. Practice
+// games are never recorded, so they never count.
+use crate::access;
+use crate::daily;
+use crate::game::Difficulty;
+use crate::history::GameRecord;
+use crate::i18n;
+use crate::profile::Profile;
+
+// Widest bar in the histogram, and most rows before attempts are grouped.
+const BAR_WIDTH: usize = 40;
+const MAX_ROWS: usize = 15;
+
+pub struct Settings {
+    // only games of this mode, e.g. "classic", "daily" or "liar" for liar-1
+    // to liar-3
+    mode: Option<String>,
+    // days since 1970-01-01, both included
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+fn date(flag: &str, value: Option<&String>) -> Result<i64, String> {
+    value
+        .and_then(|value| daily::parse_date(value))
+        .ok_or_else(|| format!("{} needs a date like 2024-03-31", flag))
+}
+
+impl Settings {
+    // stats [--mode MODE] [--from YYYY-MM-DD] [--to YYYY-MM-DD]
+    pub fn from_args(args: &[String]) -> Result<Settings, String> {
+        let mut settings = Settings {
+            mode: None,
+            from: None,
+            to: None,
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--mode" => {
+                    let mode = args.next().ok_or("--mode needs a mode")?;
+                    settings.mode = Some(mode.clone());
+                }
+                "--from" => settings.from = Some(date(arg, args.next())?),
+                "--to" => settings.to = Some(date(arg, args.next())?),
+                _ => return Err(format!("Unexpected '{}'", arg)),
+            }
+        }
+        if let (Some(from), Some(to)) = (settings.from, settings.to)
+            && from > to
+        {
+            return Err(String::from("--from is after --to"));
+        }
+        Ok(settings)
+    }
+
+    fn includes(&self, game: &GameRecord) -> bool {
+        let day = day_of(game);
+        self.mode.as_ref().is_none_or(|mode| game.is_mode(mode))
+            && self.from.is_none_or(|from| day >= from)
+            && self.to.is_none_or(|to| day <= to)
+    }
+
+    // e.g. " (classic games, from 2024-01-01)", or nothing without filters
+    fn describe(&self) -> String {
+        let mut filters = Vec::new();
+        if let Some(mode) = &self.mode {
+            filters.push(i18n::text("stats.mode", &[("mode", mode)]));
+        }
+        if let Some(from) = self.from {
+            let date = daily::format_date(from);
+            filters.push(i18n::text("stats.from", &[("date", &date)]));
+        }
+        if let Some(to) = self.to {
+            let date = daily::format_date(to);
+            filters.push(i18n::text("stats.until", &[("date", &date)]));
+        }
+        if filters.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", filters.join(", "))
+        }
+    }
+}
+
+fn day_of(game: &GameRecord) -> i64 {
+    (game.finished_at / 86_400) as i64
+}
+
+fn percent(part: usize, whole: usize) -> f64 {
+    100.0 * part as f64 / whole as f64
+}
+
+fn average(values: &[usize]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<usize>() as f64 / values.len() as f64)
+}
+
+fn attempts_to_win(games: &[&GameRecord]) -> Vec<usize> {
+    games
+        .iter()
+        .filter(|game| game.won)
+        .map(|game| game.attempts)
+        .collect()
+}
+
+fn summary(games: &[&GameRecord]) {
+    let wins = attempts_to_win(games);
+    let percent = format!("{:.0}", percent(wins.len(), games.len()));
+    println!(
+        "{}",
+        i18n::plural(
+            "stats.summary",
+            games.len() as u64,
+            &[("won", &wins.len()), ("percent", &percent)]
+        )
+    );
+    if let Some(average) = average(&wins) {
+        let average = format!("{:.2}", average);
+        println!("{}", i18n::text("stats.average", &[("average", &average)]));
+    }
+}
+
+fn by_difficulty(games: &[&GameRecord]) {
+    let rows: Vec<(&str, Vec<&GameRecord>)> = Difficulty::ALL
+        .iter()
+        .map(|&difficulty| {
+            let games = games
+                .iter()
+                .filter(|game| game.difficulty == Some(difficulty))
+                .copied()
+                .collect();
+            (difficulty.name(), games)
+        })
+        .filter(|(_, games): &(_, Vec<_>)| !games.is_empty())
+        .collect();
+    if rows.is_empty() {
+        return;
+    }
+    println!();
+    println!("{}", i18n::text("stats.difficulty_table", &[]));
+    for (name, games) in rows {
+        let wins = attempts_to_win(&games);
+        let average = average(&wins).map_or(String::from("-"), |a| format!("{:.2}", a));
+        println!(
+            "{:<10}  {:>5}  {:>4}  {:>4.0}%  {:>5}",
+            name,
+            games.len(),
+            wins.len(),
+            percent(wins.len(), games.len()),
+            average
+        );
+    }
+}
+
+// e.g. "2024-03-31, classic on hard: won in 9 attempts (41.2s)"
+fn describe_game(game: &GameRecord) -> String {
+    let difficulty = game.difficulty.map_or(String::new(), |d| {
+        format!(" {}", i18n::text("stats.on", &[("difficulty", &d.name())]))
+    });
+    let key = if game.won {
+        "stats.won_in"
+    } else {
+        "stats.lost_after"
+    };
+    let seconds = format!("{:.1}", game.millis as f64 / 1000.0);
+    format!(
+        "{}, {}{}: {}",
+        daily::format_date(day_of(game)),
+        game.mode,
+        difficulty,
+        i18n::plural(key, game.attempts as u64, &[("seconds", &seconds)])
+    )
+}
+
+// Wins before losses, then fewer attempts, then quicker.
+fn best_and_worst(games: &[&GameRecord]) {
+    let key = |game: &&&GameRecord| (!game.won, game.attempts, game.millis);
+    if let (Some(best), Some(worst)) = (games.iter().min_by_key(key), games.iter().max_by_key(key))
+    {
+        println!();
+        let (best, worst) = (describe_game(best), describe_game(worst));
+        println!("{}", i18n::text("stats.best", &[("game", &best)]));
+        println!("{}", i18n::text("stats.worst", &[("game", &worst)]));
+    }
+}
+
+// Games are kept in the order they finished.
+fn streaks(games: &[&GameRecord]) {
+    let (mut current, mut longest) = (0, 0);
+    for game in games {
+        current = if game.won { current + 1 } else { 0 };
+        longest = longest.max(current);
+    }
+    println!(
+        "{}",
+        i18n::text(
+            "stats.streak",
+            &[("current", &current), ("longest", &longest)]
+        )
+    );
+}
+
+// Binary search's worst case against the attempts actually taken, month by
+// month. Only won games where binary search applies count. Luck can beat
+// the worst case, but efficiency stops at 100%.
+fn efficiency(games: &[&GameRecord]) {
+    let mut months: Vec<(String, usize, usize, u32)> = Vec::new();
+    for game in games.iter().filter(|game| game.won) {
+        let Some(optimal) = game.optimal else {
+            continue;
+        };
+        let month = daily::format_date(day_of(game))[..7].to_string();
+        match months.last_mut() {
+            Some((last, count, attempts, total)) if *last == month => {
+                *count += 1;
+                *attempts += game.attempts;
+                *total += optimal;
+            }
+            _ => months.push((month, 1, game.attempts, optimal)),
+        }
+    }
+    if months.is_empty() {
+        return;
+    }
+    println!();
+    println!("{}", i18n::text("stats.month_table", &[]));
+    for (month, count, attempts, optimal) in months {
+        println!(
+            "{}  {:>5}  {:>8.2}  {:>7.2}  {:>9.0}%",
+            month,
+            count,
+            attempts as f64 / count as f64,
+            optimal as f64 / count as f64,
+            (100.0 * optimal as f64 / attempts as f64).min(100.0)
+        );
+    }
+}
+
+// How many wins took each number of attempts. Long tails are grouped so the
+// chart stays short; bars are left out in accessible mode.
+fn histogram(games: &[&GameRecord]) {
+    let wins = attempts_to_win(games);
+    let (Some(&low), Some(&high)) = (wins.iter().min(), wins.iter().max()) else {
+        return;
+    };
+    let size = (high - low) / MAX_ROWS + 1;
+    let mut counts = vec![0; (high - low) / size + 1];
+    for attempts in &wins {
+        counts[(attempts - low) / size] += 1;
+    }
+    let most = counts.iter().copied().max().unwrap_or(1);
+    println!();
+    println!("{}", i18n::text("stats.histogram", &[]));
+    for (row, &count) in counts.iter().enumerate() {
+        let first = low + row * size;
+        let label = if size == 1 {
+            first.to_string()
+        } else {
+            format!("{}-{}", first, first + size - 1)
+        };
+        if access::enabled() {
+            let games = i18n::plural("count.games", count as u64, &[]);
+            println!("{:>9}: {}", label, games);
+        } else {
+            let bar = "█".repeat((count * BAR_WIDTH).div_ceil(most));
+            println!(
+                "{:>9} | {}",
+                label,
+                format!("{} {}", bar, count).trim_start()
+            );
+        }
+    }
+}
+
+pub fn show(player: &str, settings: Settings) -> Result<(), String> {
+    let profile = Profile::load(player)?;
+    let games: Vec<&GameRecord> = profile
+        .history
+        .iter()
+        .filter(|game| settings.includes(game))
+        .collect();
+    if games.is_empty() {
+        println!(
+            "{}",
+            i18n::text(
+                "stats.none",
+                &[("player", &profile.name), ("filters", &settings.describe())]
+            )
+        );
+        return Ok(());
+    }
+    println!(
+        "{}",
+        i18n::text(
+            "stats.title",
+            &[("player", &profile.name), ("filters", &settings.describe())]
+        )
+    );
+    println!();
+    summary(&games);
+    streaks(&games);
+    by_difficulty(&games);
+    best_and_worst(&games);
+    efficiency(&games);
+    histogram(&games);
+    Ok(())
+}
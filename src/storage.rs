@@ -0,0 +1,173 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::env;
+#[cfg(any(feature = "json", feature = "yaml"))]
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Where profiles and other saved files live: $GUESSING_GAME_HOME, or
+// ~/.guessing_game.
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("GUESSING_GAME_HOME") {
+        return PathBuf::from(dir);
+    }
+    let home = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .unwrap_or_else(|| ".".into());
+    Path::new(&home).join(".guessing_game")
+}
+
+// Names end up in file names, so keep them to a safe alphabet.
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// Ok(None) when the file doesn't exist yet.
+#[cfg(feature = "json")]
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Can't read {}: {}", path.display(), e)),
+    };
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| format!("{} is damaged: {}", path.display(), e))
+}
+
+#[cfg(feature = "json")]
+pub fn save<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    write(path, &text)
+}
+
+// Like `load` and `save`, but the file isn't readable at a glance. Meant for
+// things like saved games, which give the secret away; anyone determined can
+// still undo it.
+#[cfg(feature = "json")]
+pub fn load_scrambled<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Can't read {}: {}", path.display(), e)),
+    };
+    let damaged = || format!("{} is damaged", path.display());
+    let (header, body) = text.trim().split_once('\n').ok_or_else(damaged)?;
+    let nonce = header
+        .strip_prefix(SCRAMBLED_HEADER)
+        .and_then(|n| u64::from_str_radix(n, 16).ok())
+        .ok_or_else(damaged)?;
+    let mut bytes = (0..body.len())
+        .step_by(2)
+        .map(|i| {
+            body.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(damaged)?;
+    scramble(&mut bytes, nonce);
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|e| format!("{} is damaged: {}", path.display(), e))
+}
+
+#[cfg(feature = "json")]
+pub fn save_scrambled<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let mut bytes = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+    let nonce = rand::random::<u64>();
+    scramble(&mut bytes, nonce);
+    let body: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    write(
+        path,
+        &format!("{}{:016x}\n{}\n", SCRAMBLED_HEADER, nonce, body),
+    )
+}
+
+#[cfg(feature = "json")]
+const SCRAMBLED_HEADER: &str = "guessing-game scrambled ";
+
+#[cfg(feature = "json")]
+// XORs with a keystream from the nonce, so scrambling twice undoes it.
+fn scramble(bytes: &mut [u8], nonce: u64) {
+    let mut state = nonce ^ 0x6775_6573_735f_6761;
+    for chunk in bytes.chunks_mut(8) {
+        // splitmix64
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        for (byte, key) in chunk.iter_mut().zip(z.to_le_bytes()) {
+            *byte ^= key;
+        }
+    }
+}
+
+#[cfg(any(feature = "json", feature = "yaml"))]
+// Write then rename, so a crash never leaves half a file behind.
+pub fn write(path: &Path, text: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Can't create {}: {}", dir.display(), e))?;
+    }
+    let partial = path.with_extension("tmp");
+    fs::write(&partial, text).map_err(|e| format!("Can't write {}: {}", path.display(), e))?;
+    fs::rename(&partial, path).map_err(|e| format!("Can't write {}: {}", path.display(), e))
+}
+
+#[cfg(not(feature = "json"))]
+pub fn load<T: DeserializeOwned>(_: &Path) -> Result<Option<T>, String> {
+    Err(String::from("Saving needs the `json` feature"))
+}
+
+#[cfg(not(feature = "json"))]
+pub fn save<T: Serialize>(_: &Path, _: &T) -> Result<(), String> {
+    Err(String::from("Saving needs the `json` feature"))
+}
+
+#[cfg(not(feature = "json"))]
+pub fn load_scrambled<T: DeserializeOwned>(_: &Path) -> Result<Option<T>, String> {
+    Err(String::from("Saving needs the `json` feature"))
+}
+
+#[cfg(not(feature = "json"))]
+pub fn save_scrambled<T: Serialize>(_: &Path, _: &T) -> Result<(), String> {
+    Err(String::from("Saving needs the `json` feature"))
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn scrambled_files_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("guessing-game-test-{}", std::process::id()))
+            .join("secret.sav");
+        let value = BTreeMap::from([("secret", 4_815_162_342u64), ("attempts", 3)]);
+
+        save_scrambled(&path, &value).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with(SCRAMBLED_HEADER));
+        assert!(!text.contains("secret") && !text.contains("4815162342"));
+        let loaded: Option<BTreeMap<String, u64>> = load_scrambled(&path).unwrap();
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+
+        let expected = value.iter().map(|(k, v)| (k.to_string(), *v)).collect();
+        assert_eq!(loaded, Some(expected));
+    }
+
+    #[test]
+    fn scrambling_twice_undoes_it() {
+        let original = b"{\"seed\":7,\"inputs\":[\"50\",\"25\"]}".to_vec();
+        let mut bytes = original.clone();
+        scramble(&mut bytes, 42);
+        assert_ne!(bytes, original);
+        scramble(&mut bytes, 42);
+        assert_eq!(bytes, original);
+    }
+}
@@ -0,0 +1,170 @@
+// Colors and text styles for each kind of message. A style is a list of
+// words: bold, dim, italic, underline, a color name (red, bright_red, ...) or
+// #rrggbb. Themes come from THEMES or from config.yaml:
+//
+//   theme: mine
+//   themes:
+//     mine:
+//       too_small: bold cyan
+//       too_big: "#ff8700"
+//
+// Kinds a theme leaves out are printed plain, and so is everything when
+// stdout isn't a terminal or color is off (see access.rs).
+use crate::access;
+use crate::color::Color;
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    Prompt,
+    TooSmall,
+    TooBig,
+    Win,
+    Loss,
+    // explanations and other help along the way
+    Hint,
+    Error,
+}
+
+pub const DEFAULT: &str = "classic";
+
+pub struct Builtin {
+    pub name: &'static str,
+    pub styles: &'static [(Kind, &'static str)],
+}
+
+pub const THEMES: &[Builtin] = &[
+    Builtin {
+        name: "classic",
+        styles: &[
+            (Kind::Prompt, "bold"),
+            (Kind::TooSmall, "cyan"),
+            (Kind::TooBig, "yellow"),
+            (Kind::Win, "bold green"),
+            (Kind::Loss, "bold red"),
+            (Kind::Hint, "dim"),
+            (Kind::Error, "red"),
+        ],
+    },
+    Builtin {
+        name: "ocean",
+        styles: &[
+            (Kind::Prompt, "bold #5fafd7"),
+            (Kind::TooSmall, "#87d7ff"),
+            (Kind::TooBig, "#0087af"),
+            (Kind::Win, "bold #00d7af"),
+            (Kind::Loss, "#d75f5f"),
+            (Kind::Hint, "italic #5f8787"),
+            (Kind::Error, "#d75f87"),
+        ],
+    },
+    Builtin {
+        name: "high_contrast",
+        styles: &[
+            (Kind::Prompt, "bold bright_white"),
+            (Kind::TooSmall, "bold underline bright_cyan"),
+            (Kind::TooBig, "bold bright_yellow"),
+            (Kind::Win, "bold bright_green"),
+            (Kind::Loss, "bold bright_red"),
+            (Kind::Hint, "bright_white"),
+            (Kind::Error, "bold underline bright_magenta"),
+        ],
+    },
+    Builtin {
+        name: "plain",
+        styles: &[],
+    },
+];
+
+// Kind -> SGR parameters, e.g. "1;32" for bold green.
+pub type Theme = HashMap<Kind, String>;
+
+static CURRENT: OnceLock<Theme> = OnceLock::new();
+
+const COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+fn parse_style(spec: &str) -> Result<String, String> {
+    let mut codes = Vec::new();
+    for word in spec.split_whitespace() {
+        let code = match word {
+            "bold" => String::from("1"),
+            "dim" => String::from("2"),
+            "italic" => String::from("3"),
+            "underline" => String::from("4"),
+            hex if hex.starts_with('#') => {
+                let Color(r, g, b) = Color::parse(hex)?;
+                format!("38;2;{};{};{}", r, g, b)
+            }
+            name => {
+                let (base, bright) = match name.strip_prefix("bright_") {
+                    Some(base) => (base, true),
+                    None => (name, false),
+                };
+                let index = COLORS
+                    .iter()
+                    .position(|&c| c == base)
+                    .ok_or_else(|| format!("'{}' is not a style", word))?;
+                let first = if bright { 90 } else { 30 };
+                (first + index).to_string()
+            }
+        };
+        codes.push(code);
+    }
+    Ok(codes.join(";"))
+}
+
+fn build<'a>(styles: impl IntoIterator<Item = (Kind, &'a str)>) -> Result<Theme, String> {
+    styles
+        .into_iter()
+        .map(|(kind, spec)| {
+            parse_style(spec)
+                .map(|codes| (kind, codes))
+                .map_err(|e| format!("In style \"{}\": {}", spec, e))
+        })
+        .collect()
+}
+
+// The theme called `name`; the user's themes come before the built-in ones
+// of the same name.
+pub fn find(name: &str, user: &BTreeMap<String, BTreeMap<Kind, String>>) -> Result<Theme, String> {
+    match (user.get(name), THEMES.iter().find(|t| t.name == name)) {
+        (Some(styles), _) => build(styles.iter().map(|(&kind, spec)| (kind, spec.as_str())))
+            .map_err(|e| format!("Theme {}: {}", name, e)),
+        (None, Some(builtin)) => build(builtin.styles.iter().copied()),
+        (None, None) => {
+            let names: Vec<&str> = THEMES.iter().map(|t| t.name).collect();
+            Err(format!(
+                "There's no theme called '{}'. Built in: {}",
+                name,
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+// Picks the configured theme. Errors are for bad config, even when color is
+// off, so they don't go unnoticed.
+pub fn init(config: &Config) -> Result<(), String> {
+    let theme = find(config.theme.as_deref().unwrap_or(DEFAULT), &config.themes)?;
+    if io::stdout().is_terminal() && access::color() {
+        let _ = CURRENT.set(theme);
+    }
+    Ok(())
+}
+
+// `text` in the style for `kind`, or unchanged when there's no theme.
+pub fn paint(kind: Kind, text: &str) -> String {
+    match CURRENT.get().and_then(|theme| theme.get(&kind)) {
+        Some(codes) if !codes.is_empty() && !text.is_empty() => {
+            format!("\x1b[{}m{}\x1b[0m", codes, text)
+        }
+        _ => String::from(text),
+    }
+}
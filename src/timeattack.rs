@@ -0,0 +1,219 @@
+use crate::game::{self, Game};
+use crate::history::GameRecord;
+use crate::i18n;
+use crate::mode::{GameMode, Score, Setup};
+use crate::theme::{self, Kind};
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+pub struct Settings {
+    // whole game; None for no limit
+    pub budget: Option<Duration>,
+    // each guess; None for no limit
+    pub per_guess: Option<Duration>,
+}
+
+impl Settings {
+    // timeattack [budget_secs] [per_guess_secs], 0 switches a limit off
+    pub fn from_args(args: &[String]) -> Result<Settings, String> {
+        let seconds = |i: usize, default: u64| -> Result<Option<Duration>, String> {
+            let secs = match args.get(i) {
+                Some(arg) => arg
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a number of seconds", arg))?,
+                None => default,
+            };
+            Ok((secs > 0).then(|| Duration::from_secs(secs)))
+        };
+        let settings = Settings {
+            budget: seconds(0, 60)?,
+            per_guess: seconds(1, 10)?,
+        };
+        if settings.budget.is_none() && settings.per_guess.is_none() {
+            return Err(String::from("Time attack needs at least one time limit"));
+        }
+        Ok(settings)
+    }
+}
+
+// Starting score; each turn and each second used takes some away.
+const BASE_SCORE: u64 = 1000;
+const TURN_COST: u64 = 50;
+const SECOND_COST: u64 = 10;
+
+struct Turn {
+    // None when the turn timed out
+    guess: Option<u32>,
+    result: Option<Ordering>,
+    elapsed: Duration,
+}
+
+fn seconds(d: Duration) -> String {
+    format!("{:.1}s", d.as_secs_f64())
+}
+
+pub struct TimeAttack {
+    settings: Settings,
+    game: Game<u32>,
+    start: Instant,
+    game_end: Option<Instant>,
+    turn_start: Instant,
+    turns: Vec<Turn>,
+}
+
+impl TimeAttack {
+    pub fn new(settings: Settings, setup: &Setup) -> TimeAttack {
+        let start = Instant::now();
+        TimeAttack {
+            game_end: settings.budget.map(|b| start + b),
+            settings,
+            game: Game::seeded(1u32, 100, 0, setup.seed),
+            start,
+            turn_start: start,
+            turns: Vec::new(),
+        }
+    }
+
+    fn turn_end(&self) -> Option<Instant> {
+        self.settings.per_guess.map(|p| self.turn_start + p)
+    }
+
+    fn score_points(&self) -> u32 {
+        if !self.game.won() {
+            return 0;
+        }
+        let cost =
+            TURN_COST * self.turns.len() as u64 + SECOND_COST * self.start.elapsed().as_secs();
+        BASE_SCORE.saturating_sub(cost) as u32
+    }
+}
+
+impl GameMode for TimeAttack {
+    fn name(&self) -> String {
+        String::from("timeattack")
+    }
+
+    fn intro(&self) -> Vec<String> {
+        let mut intro = vec![i18n::text("timeattack.intro", &[])];
+        if let Some(budget) = self.settings.budget {
+            intro.push(i18n::plural("timeattack.budget", budget.as_secs(), &[]));
+        }
+        if let Some(per_guess) = self.settings.per_guess {
+            intro.push(i18n::plural(
+                "timeattack.per_guess",
+                per_guess.as_secs(),
+                &[],
+            ));
+        }
+        intro
+    }
+
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String> {
+        // the same checks and answers as a classic game, accessible ones too
+        let mut lines = game::answer(&mut self.game, input)?;
+        let Some(&(guess, result)) = self.game.history().last() else {
+            return Ok(lines);
+        };
+        let elapsed = self.turn_start.elapsed();
+        self.turns.push(Turn {
+            guess: Some(guess),
+            result: Some(result),
+            elapsed,
+        });
+        self.turn_start = Instant::now();
+        lines.push(i18n::text(
+            "timeattack.took",
+            &[("time", &seconds(elapsed))],
+        ));
+        Ok(lines)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.game.won() || self.game_end.is_some_and(|end| Instant::now() >= end)
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        match (self.game_end, self.turn_end()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn status(&self) -> String {
+        let now = Instant::now();
+        // round up, so "0s" is never shown while time remains
+        let left = |end: Instant| {
+            end.saturating_duration_since(now)
+                .as_millis()
+                .div_ceil(1000)
+        };
+        let total = i18n::text("timeattack.total", &[]);
+        let turn = i18n::text("timeattack.turn", &[]);
+        match (self.game_end, self.turn_end()) {
+            (Some(g), Some(t)) => format!("[{} {:>3}s | {} {:>3}s]", total, left(g), turn, left(t)),
+            (Some(g), None) => format!("[{} {:>3}s]", total, left(g)),
+            (None, Some(t)) => format!("[{} {:>3}s]", turn, left(t)),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn time_up(&mut self) -> Vec<String> {
+        self.turns.push(Turn {
+            guess: None,
+            result: None,
+            elapsed: self.turn_start.elapsed(),
+        });
+        self.turn_start = Instant::now();
+        vec![i18n::text("timeattack.time_up", &[])]
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if !self.game.won() {
+            if self.game_end.is_some_and(|end| Instant::now() >= end) {
+                lines.push(i18n::text("timeattack.out_of_time", &[]));
+            }
+            lines.push(theme::paint(
+                Kind::Loss,
+                &i18n::text("end.reveal", &[("secret", &self.game.secret())]),
+            ));
+        }
+
+        lines.push(i18n::text("timeattack.table", &[]));
+        for (i, turn) in self.turns.iter().enumerate() {
+            let guess = turn.guess.map_or(String::from("-"), |g| g.to_string());
+            let result = match turn.result {
+                Some(Ordering::Less) => i18n::text("table.too_small", &[]),
+                Some(Ordering::Greater) => i18n::text("table.too_big", &[]),
+                Some(Ordering::Equal) => i18n::text("table.hit", &[]),
+                None => i18n::text("table.missed", &[]),
+            };
+            lines.push(format!(
+                "{:>4}  {:>5}  {:>9}  {}",
+                i + 1,
+                guess,
+                seconds(turn.elapsed),
+                result
+            ));
+        }
+        let total = seconds(self.start.elapsed());
+        lines.push(i18n::text("timeattack.total_time", &[("time", &total)]));
+        lines
+    }
+
+    fn score(&self) -> Score {
+        Score {
+            won: self.game.won(),
+            attempts: self.turns.len(),
+            points: Some(self.score_points()),
+        }
+    }
+
+    fn record(&self, millis: u64) -> Option<GameRecord> {
+        let won = self.game.won();
+        (!self.turns.is_empty()).then(|| GameRecord {
+            attempts: self.turns.len(),
+            ..GameRecord::from_game("timeattack", &self.game, won, millis)
+        })
+    }
+}
@@ -0,0 +1,137 @@
+use crate::access;
+use std::io::{self, IsTerminal, Write};
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// What came back from a prompt.
+pub enum Input {
+    Line(String),
+    TimedOut,
+    // Ctrl-C
+    Interrupted,
+    Closed,
+}
+
+// Stdin read on a background thread, so a prompt can give up waiting or be
+// interrupted. `read_line` on stdin itself can't be. There is one reader for
+// the whole run, shared by every TimedInput, so no line is ever read by a
+// game that has already ended.
+pub struct TimedInput {
+    live: bool,
+}
+
+struct Keyboard {
+    inputs: Mutex<Receiver<Input>>,
+    // Ctrl-C goes in with the typed lines
+    interrupts: Sender<Input>,
+}
+
+static KEYBOARD: OnceLock<Keyboard> = OnceLock::new();
+// set once stdin has run out, after which every read is Closed
+static CLOSED: AtomicBool = AtomicBool::new(false);
+// how many TimedInputs are waiting to hear about Ctrl-C
+static LISTENING: AtomicUsize = AtomicUsize::new(0);
+
+fn keyboard() -> &'static Keyboard {
+    KEYBOARD.get_or_init(|| {
+        let (sender, inputs) = mpsc::channel();
+        let interrupts = sender.clone();
+        thread::spawn(move || read_stdin(sender));
+        let _ = ctrlc::set_handler(interrupted);
+        Keyboard {
+            inputs: Mutex::new(inputs),
+            interrupts,
+        }
+    })
+}
+
+// With a game waiting for input, Ctrl-C comes back from its prompt as
+// Interrupted. With nobody waiting, it quits as it would without a handler.
+fn interrupted() {
+    let listening = LISTENING.load(Ordering::SeqCst) > 0;
+    if !listening || keyboard().interrupts.send(Input::Interrupted).is_err() {
+        process::exit(130);
+    }
+}
+
+// Anything that isn't a line, invalid UTF-8 included, ends the input.
+fn read_stdin(sender: Sender<Input>) {
+    loop {
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => {
+                CLOSED.store(true, Ordering::SeqCst);
+                let _ = sender.send(Input::Closed);
+                return;
+            }
+            Ok(_) => {
+                if sender.send(Input::Line(line)).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for TimedInput {
+    fn drop(&mut self) {
+        LISTENING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl TimedInput {
+    pub fn new() -> TimedInput {
+        keyboard();
+        LISTENING.fetch_add(1, Ordering::SeqCst);
+        TimedInput {
+            // redrawing the countdown only makes sense on a real terminal, and
+            // not at all to a screen reader
+            live: io::stdout().is_terminal() && access::redraw(),
+        }
+    }
+
+    // Waits for a line, or until `deadline` if there is one. While waiting,
+    // `status` is redrawn about once a second at the start of the prompt line.
+    pub fn read_line(&self, deadline: Option<Instant>, status: impl Fn() -> String) -> Input {
+        let Ok(inputs) = keyboard().inputs.lock() else {
+            return Input::Closed;
+        };
+        if let Ok(input) = inputs.try_recv() {
+            return input;
+        }
+        // the reader has stopped, so nothing more will come
+        if CLOSED.load(Ordering::SeqCst) {
+            return Input::Closed;
+        }
+        let Some(deadline) = deadline else {
+            return inputs.recv().unwrap_or(Input::Closed);
+        };
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                if self.live {
+                    println!();
+                }
+                return Input::TimedOut;
+            }
+            let left = deadline - now;
+            if self.live {
+                // save cursor, redraw the status at column 0, restore cursor
+                print!("\x1b7\r{}\x1b8", status());
+                let _ = io::stdout().flush();
+            }
+
+            // wake up on the next whole second to redraw
+            let tick = Duration::from_millis(u64::from(left.subsec_millis()).max(1));
+            match inputs.recv_timeout(tick.min(left)) {
+                Ok(input) => return input,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => return Input::Closed,
+            }
+        }
+    }
+}
@@ -0,0 +1,592 @@
+use crate::cli::{self, Error};
+use crate::game::Game;
+use crate::i18n;
+use crate::mode;
+use crate::replay::Recording;
+use crate::storage;
+use crate::timer::{Input, TimedInput};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::path::PathBuf;
+use std::time::Instant;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    SingleElimination,
+    RoundRobin,
+}
+
+// One player's go at a match secret.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct Score {
+    pub attempts: usize,
+    pub millis: u64,
+}
+
+impl Score {
+    // Fewer attempts wins; time breaks ties.
+    fn cmp_better(&self, other: &Score) -> Ordering {
+        (self.attempts, self.millis).cmp(&(other.attempts, other.millis))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Match {
+    // None is a bye
+    pub home: Option<String>,
+    pub away: Option<String>,
+    // both players get the secret from this seed; drawn when the match is
+    // played, so it's never on disk while the secret could still be used
+    #[serde(default)]
+    pub seed: Option<u64>,
+    pub result: Option<(Score, Score)>,
+}
+
+impl Match {
+    fn new(home: Option<String>, away: Option<String>) -> Match {
+        Match {
+            home,
+            away,
+            seed: None,
+            result: None,
+        }
+    }
+
+    fn is_bye(&self) -> bool {
+        self.home.is_none() || self.away.is_none()
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_bye() || self.result.is_some()
+    }
+
+    // In a knockout someone has to go through: a dead heat goes to the
+    // higher seed, who always plays at home.
+    fn knockout_winner(&self) -> Option<&String> {
+        self.winner()
+            .or_else(|| self.result.as_ref().and(self.home.as_ref()))
+    }
+
+    // The winner, or None for an unplayed match or a dead heat.
+    fn winner(&self) -> Option<&String> {
+        match (&self.home, &self.away, &self.result) {
+            (Some(home), None, _) => Some(home),
+            (None, Some(away), _) => Some(away),
+            (Some(home), Some(away), Some((h, a))) => match h.cmp_better(a) {
+                Ordering::Less => Some(home),
+                Ordering::Greater => Some(away),
+                Ordering::Equal => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Tournament {
+    pub format: Format,
+    // in seeding order
+    pub players: Vec<String>,
+    pub rounds: Vec<Vec<Match>>,
+}
+
+fn path() -> PathBuf {
+    storage::data_dir().join("tournament.json")
+}
+
+impl Tournament {
+    pub fn new(format: Format, players: Vec<String>) -> Tournament {
+        let rounds = match format {
+            Format::SingleElimination => vec![first_bracket_round(&players)],
+            Format::RoundRobin => round_robin(&players),
+        };
+        Tournament {
+            format,
+            players,
+            rounds,
+        }
+    }
+
+    pub fn load() -> Result<Tournament, String> {
+        storage::load(&path())?.ok_or_else(|| i18n::text("tournament.none", &[]))
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        storage::save(&path(), self)
+    }
+
+    // (round, match) of the next match that still has to be played.
+    fn next_match(&self) -> Option<(usize, usize)> {
+        self.rounds
+            .iter()
+            .enumerate()
+            .find_map(|(r, round)| round.iter().position(|m| !m.is_done()).map(|i| (r, i)))
+    }
+
+    // Single elimination adds the next round once the current one is over.
+    fn advance(&mut self) {
+        if self.format != Format::SingleElimination || self.next_match().is_some() {
+            return;
+        }
+        let last = &self.rounds[self.rounds.len() - 1];
+        if last.len() == 1 {
+            return;
+        }
+        let winners: Vec<Option<String>> =
+            last.iter().map(|m| m.knockout_winner().cloned()).collect();
+        // Neighbouring matches in bracket order feed the same next match,
+        // where the higher seed plays at home whatever the line-up.
+        let seed = |player: &Option<String>| {
+            player
+                .as_ref()
+                .and_then(|p| self.players.iter().position(|q| q == p))
+        };
+        let next = winners
+            .chunks(2)
+            .map(|pair| {
+                let (mut home, mut away) = (pair[0].clone(), pair[1].clone());
+                if away.is_some() && seed(&away) < seed(&home) {
+                    std::mem::swap(&mut home, &mut away);
+                }
+                Match::new(home, away)
+            })
+            .collect();
+        self.rounds.push(next);
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.next_match().is_none()
+            && (self.format == Format::RoundRobin
+                || self.rounds.last().is_some_and(|r| r.len() == 1))
+    }
+}
+
+// Power-of-two bracket; top seeds meet the bottom seeds and get the byes.
+// The matches are in bracket order, so the top two seeds can only meet in
+// the final, the top four in the semi-finals and so on.
+fn first_bracket_round(players: &[String]) -> Vec<Match> {
+    let size = players.len().next_power_of_two();
+    let seed = |i: usize| players.get(i).cloned();
+    bracket_order(size)
+        .chunks(2)
+        .map(|pair| Match::new(seed(pair[0]), seed(pair[1])))
+        .collect()
+}
+
+// Seeds (from 0) down a bracket of `size` lines: 0 7 3 4 1 6 2 5 for eight.
+// Each doubling puts every seed next to the one it meets first.
+fn bracket_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let last = order.len() * 2 - 1;
+        order = order.iter().flat_map(|&s| [s, last - s]).collect();
+    }
+    order
+}
+
+// Circle method: everybody plays everybody once.
+fn round_robin(players: &[String]) -> Vec<Vec<Match>> {
+    let mut circle: Vec<Option<String>> = players.iter().cloned().map(Some).collect();
+    if circle.len() % 2 == 1 {
+        circle.push(None);
+    }
+    let n = circle.len();
+    let mut rounds = Vec::new();
+    for _ in 0..n - 1 {
+        let round: Vec<Match> = (0..n / 2)
+            .map(|i| Match::new(circle[i].clone(), circle[n - 1 - i].clone()))
+            .filter(|m| !m.is_bye())
+            .collect();
+        rounds.push(round);
+        // keep the first seat fixed and rotate everyone else
+        let last = circle.pop().expect("at least two seats");
+        circle.insert(1, last);
+    }
+    rounds
+}
+
+fn describe(m: &Match) -> String {
+    let name = |p: &Option<String>| {
+        p.clone()
+            .unwrap_or_else(|| i18n::text("tournament.bye", &[]))
+    };
+    let (home, away) = (name(&m.home), name(&m.away));
+    match (&m.result, m.winner()) {
+        _ if m.is_bye() => i18n::text(
+            "tournament.advances",
+            &[("player", &m.winner().map_or("", String::as_str))],
+        ),
+        (None, _) => i18n::text("tournament.match", &[("home", &home), ("away", &away)]),
+        (Some((h, a)), winner) => {
+            let score = |s: &Score| {
+                let seconds = format!("{:.1}", s.millis as f64 / 1000.0);
+                i18n::plural(
+                    "tournament.score",
+                    s.attempts as u64,
+                    &[("seconds", &seconds)],
+                )
+            };
+            let verdict = match winner {
+                Some(w) => i18n::text("tournament.wins", &[("player", w)]),
+                None => i18n::text("tournament.draw", &[]),
+            };
+            format!(
+                "{} ({}) vs {} ({}): {}",
+                home,
+                score(h),
+                away,
+                score(a),
+                verdict
+            )
+        }
+    }
+}
+
+pub fn show(tournament: &Tournament) {
+    let key = match tournament.format {
+        Format::SingleElimination => "tournament.single",
+        Format::RoundRobin => "tournament.round_robin",
+    };
+    println!(
+        "{}",
+        i18n::plural(key, tournament.players.len() as u64, &[])
+    );
+    for (r, round) in tournament.rounds.iter().enumerate() {
+        println!("{}", i18n::text("tournament.round", &[("round", &(r + 1))]));
+        for m in round {
+            println!("  {}", describe(m));
+        }
+    }
+
+    println!("{}", i18n::text("tournament.standings", &[]));
+    for (place, (player, note)) in standings(tournament).iter().enumerate() {
+        println!("  {:>2}. {:<16} {}", place + 1, player, note);
+    }
+    if tournament.is_over() {
+        println!("{}", i18n::text("tournament.over", &[]));
+    }
+}
+
+fn standings(tournament: &Tournament) -> Vec<(String, String)> {
+    match tournament.format {
+        Format::SingleElimination => {
+            // furthest round reached, best first; knocked out players keep
+            // their seeding order within a round
+            let mut reached: Vec<(usize, usize, &String)> = tournament
+                .players
+                .iter()
+                .enumerate()
+                .map(|(seed, player)| {
+                    let last_round = tournament
+                        .rounds
+                        .iter()
+                        .rposition(|round| {
+                            round.iter().any(|m| {
+                                m.home.as_ref() == Some(player) || m.away.as_ref() == Some(player)
+                            })
+                        })
+                        .unwrap_or(0);
+                    (last_round, seed, player)
+                })
+                .collect();
+            let champion = tournament
+                .rounds
+                .last()
+                .filter(|_| tournament.is_over())
+                .and_then(|r| r[0].knockout_winner());
+            reached.sort_by(|a, b| {
+                let won = |p: &String| Some(p) == champion;
+                won(b.2)
+                    .cmp(&won(a.2))
+                    .then(b.0.cmp(&a.0))
+                    .then(a.1.cmp(&b.1))
+            });
+            reached
+                .into_iter()
+                .map(|(round, _, player)| {
+                    let note = if Some(player) == champion {
+                        i18n::text("tournament.champion", &[])
+                    } else {
+                        i18n::text("tournament.reached", &[("round", &(round + 1))])
+                    };
+                    (player.clone(), note)
+                })
+                .collect()
+        }
+        Format::RoundRobin => {
+            let mut table: Vec<Row> = tournament
+                .players
+                .iter()
+                .map(|p| Row {
+                    player: p.clone(),
+                    ..Row::default()
+                })
+                .collect();
+            for m in tournament.rounds.iter().flatten() {
+                let Some((h, a)) = &m.result else { continue };
+                let winner = m.winner();
+                for (player, score) in [(&m.home, h), (&m.away, a)] {
+                    let Some(player) = player else { continue };
+                    let row = table
+                        .iter_mut()
+                        .find(|r| &r.player == player)
+                        .expect("registered player");
+                    row.attempts += score.attempts;
+                    match winner {
+                        Some(w) if w == player => row.won += 1,
+                        Some(_) => row.lost += 1,
+                        None => row.drawn += 1,
+                    }
+                }
+            }
+            table.sort_by(|a, b| {
+                b.points()
+                    .cmp(&a.points())
+                    .then(a.attempts.cmp(&b.attempts))
+            });
+            table
+                .into_iter()
+                .map(|row| {
+                    let note = i18n::plural(
+                        "tournament.row",
+                        row.attempts as u64,
+                        &[
+                            ("points", &row.points()),
+                            ("won", &row.won),
+                            ("drawn", &row.drawn),
+                            ("lost", &row.lost),
+                        ],
+                    );
+                    (row.player, note)
+                })
+                .collect()
+        }
+    }
+}
+
+// A round-robin table line.
+#[derive(Default)]
+struct Row {
+    player: String,
+    won: u32,
+    drawn: u32,
+    lost: u32,
+    // total over all matches; breaks ties on points
+    attempts: usize,
+}
+
+impl Row {
+    // two points a win, one a draw
+    fn points(&self) -> u32 {
+        self.won * 2 + self.drawn
+    }
+}
+
+// One player's turn at a match, with its replay still to be written. None
+// if input ran out.
+fn play_turn(player: &str, seed: u64) -> Option<(Score, Recording<u32>)> {
+    let intro = [i18n::text("tournament.turn", &[("player", &player)])];
+    let mut turn = Recording::new("tournament", &intro, Game::seeded(1u32, 100, 0, seed));
+    let start = Instant::now();
+    mode::play(&mut turn, Some(player), None).ok()?;
+    if !turn.game.won() {
+        return None;
+    }
+    let score = Score {
+        attempts: turn.game.attempts(),
+        millis: start.elapsed().as_millis() as u64,
+    };
+    Some((score, turn))
+}
+
+fn wait_for(player: &str) -> bool {
+    // scroll the last game out of sight before handing over
+    print!("{}", "\n".repeat(40));
+    println!(
+        "{}",
+        i18n::text("tournament.hand_over", &[("player", &player)])
+    );
+    let keyboard = TimedInput::new();
+    matches!(keyboard.read_line(None, String::new), Input::Line(_))
+}
+
+pub fn play_next(tournament: &mut Tournament) -> Result<(), String> {
+    let Some((r, i)) = tournament.next_match() else {
+        println!("{}", i18n::text("tournament.no_matches", &[]));
+        return Ok(());
+    };
+    let m = tournament.rounds[r][i].clone();
+    let (home, away) = (m.home.expect("not a bye"), m.away.expect("not a bye"));
+    let title = i18n::text("tournament.match", &[("home", &home), ("away", &away)]);
+    let round = i18n::text("tournament.round", &[("round", &(r + 1))]);
+    println!("{}: {}", round, title);
+
+    // Nothing is saved until both have played, so an interrupted match
+    // simply starts over on resume.
+    let interrupted = || i18n::text("tournament.interrupted", &[]);
+    let seed = rand::thread_rng().next_u64();
+    if !wait_for(&home) {
+        return Err(interrupted());
+    }
+    let (home_score, mut home_turn) = play_turn(&home, seed).ok_or_else(interrupted)?;
+    if !wait_for(&away) {
+        return Err(interrupted());
+    }
+    let (away_score, mut away_turn) = play_turn(&away, seed).ok_or_else(interrupted)?;
+
+    tournament.rounds[r][i].seed = Some(seed);
+    tournament.rounds[r][i].result = Some((home_score, away_score));
+    tournament.advance();
+    tournament.save()?;
+
+    println!("{}", describe(&tournament.rounds[r][i]));
+    // Written only now: the home replay holds the number the away player
+    // still had to find.
+    let mut lines = home_turn.keep();
+    lines.extend(away_turn.keep());
+    for line in lines {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+// tournament new [--force] <single|roundrobin> <player>... | play | show
+pub fn run(args: &[String]) -> Result<(), Error> {
+    let usage =
+        "Usage: tournament new [--force] <single|roundrobin> <player> <player>... | play | show";
+    let mut args = args.to_vec();
+    let force = cli::take_flag(&mut args, "--force");
+    match args.first().map(String::as_str) {
+        Some("new") => {
+            let format = match args.get(1).map(String::as_str) {
+                Some("single") => Format::SingleElimination,
+                Some("roundrobin") => Format::RoundRobin,
+                _ => return Err(Error::Usage(String::from(usage))),
+            };
+            let players: Vec<String> = args[2..].to_vec();
+            if players.len() < 2 {
+                return Err(Error::Usage(String::from(
+                    "A tournament needs at least two players",
+                )));
+            }
+            if (1..players.len()).any(|i| players[..i].contains(&players[i])) {
+                return Err(Error::Usage(String::from("Player names must be different")));
+            }
+            // a new one would throw away every match played so far
+            let current: Option<Tournament> = storage::load(&path())?;
+            if current.is_some_and(|current| !current.is_over()) && !force {
+                return Err(Error::Failed(i18n::text("tournament.in_progress", &[])));
+            }
+            let mut tournament = Tournament::new(format, players);
+            tournament.advance();
+            tournament.save()?;
+            show(&tournament);
+            Ok(())
+        }
+        Some("play") => {
+            let mut tournament = Tournament::load()?;
+            play_next(&mut tournament)?;
+            show(&tournament);
+            Ok(())
+        }
+        Some("show") => {
+            show(&Tournament::load()?);
+            Ok(())
+        }
+        _ => Err(Error::Usage(String::from(usage))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(n: usize) -> Vec<String> {
+        (1..=n).map(|i| format!("p{}", i)).collect()
+    }
+
+    fn pairs(round: &[Match]) -> Vec<(Option<&str>, Option<&str>)> {
+        round
+            .iter()
+            .map(|m| (m.home.as_deref(), m.away.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn everybody_meets_everybody_once() {
+        for n in 2..=9 {
+            let players = players(n);
+            let rounds = round_robin(&players);
+            assert_eq!(rounds.len(), if n % 2 == 0 { n - 1 } else { n });
+            let mut met = Vec::new();
+            for round in &rounds {
+                let mut playing: Vec<&str> = pairs(round)
+                    .into_iter()
+                    .flat_map(|(home, away)| [home.unwrap(), away.unwrap()])
+                    .collect();
+                playing.sort();
+                playing.dedup();
+                assert_eq!(playing.len(), round.len() * 2, "someone plays twice");
+                for (home, away) in pairs(round) {
+                    let (home, away) = (home.unwrap(), away.unwrap());
+                    met.push(if home < away {
+                        (home, away)
+                    } else {
+                        (away, home)
+                    });
+                }
+            }
+            met.sort();
+            met.dedup();
+            assert_eq!(met.len(), n * (n - 1) / 2);
+        }
+    }
+
+    #[test]
+    fn first_round_follows_seeding() {
+        assert_eq!(bracket_order(8), [0, 7, 3, 4, 1, 6, 2, 5]);
+        let round = first_bracket_round(&players(8));
+        assert_eq!(
+            pairs(&round),
+            [
+                (Some("p1"), Some("p8")),
+                (Some("p4"), Some("p5")),
+                (Some("p2"), Some("p7")),
+                (Some("p3"), Some("p6")),
+            ]
+        );
+        // the top seeds get the byes
+        let round = first_bracket_round(&players(5));
+        assert_eq!(
+            pairs(&round),
+            [
+                (Some("p1"), None),
+                (Some("p4"), Some("p5")),
+                (Some("p2"), None),
+                (Some("p3"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn higher_seed_plays_at_home() {
+        let mut tournament = Tournament::new(Format::SingleElimination, players(4));
+        let (fast, slow) = (
+            Score {
+                attempts: 3,
+                millis: 0,
+            },
+            Score {
+                attempts: 7,
+                millis: 0,
+            },
+        );
+        // p4 knocks out p1, p2 beats p3
+        tournament.rounds[0][0].result = Some((slow, fast));
+        tournament.rounds[0][1].result = Some((fast, slow));
+        tournament.advance();
+        assert_eq!(pairs(&tournament.rounds[1]), [(Some("p2"), Some("p4"))]);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::cli::Error;
+use crate::game::{self, Game};
+use crate::history::{self, GameRecord};
+use crate::i18n;
+use crate::mode::{GameMode, Score, Setup};
+use crate::profile::Profile;
+use crate::replay::{Input, Replay};
+use crate::theme::{self, Kind};
+use rand::{Rng, StdRng};
+use std::time::Instant;
+
+pub const STARTING_BANKROLL: u64 = 1000;
+const MAX_ATTEMPTS: usize = 10;
+// A win on this attempt gives the stake back, before the house takes its cut.
+// Binary search over 1..=100 needs about six guesses.
+const BREAK_EVEN_ATTEMPTS: u64 = 6;
+
+pub struct Settings {
+    // fraction of every payout the house keeps, 0.0..1.0
+    pub house_edge: f64,
+}
+
+impl Settings {
+    // wager [house_edge_percent]
+    pub fn from_args(args: &[String]) -> Result<Settings, String> {
+        let percent: f64 = match args.first() {
+            Some(arg) => arg
+                .parse()
+                .map_err(|_| format!("'{}' is not a percentage", arg))?,
+            None => 5.0,
+        };
+        if !(0.0..100.0).contains(&percent) {
+            return Err(String::from(
+                "The house edge must be from 0 up to 100 percent",
+            ));
+        }
+        Ok(Settings {
+            house_edge: percent / 100.0,
+        })
+    }
+}
+
+// Total returned to the player (stake included) for a win on `attempts`.
+fn payout(stake: u64, attempts: usize, house_edge: f64) -> u64 {
+    let fair = stake as f64 * BREAK_EVEN_ATTEMPTS as f64 / attempts as f64;
+    (fair * (1.0 - house_edge)).floor() as u64
+}
+
+struct Round {
+    stake: u64,
+    game: Game<u32>,
+    replay: Replay,
+    start: Instant,
+}
+
+pub struct Wager {
+    settings: Settings,
+    profile: Profile,
+    // seeds each round's game
+    rng: StdRng,
+    // None while the player is placing a bet
+    round: Option<Round>,
+    cashed_out: bool,
+}
+
+impl Wager {
+    pub fn new(settings: Settings, setup: &Setup) -> Result<Wager, Error> {
+        // every round moves real money, so it can't be played from a file
+        if setup.input_file.is_some() {
+            return Err(Error::Usage(String::from(
+                "Wager can't be played from --input-file",
+            )));
+        }
+        // nor with secrets known in advance
+        if setup.seeded {
+            return Err(Error::Usage(String::from(
+                "Wager can't be played with --seed",
+            )));
+        }
+        Ok(Wager {
+            settings,
+            profile: Profile::load(&setup.player)?,
+            rng: game::seeded_rng(setup.seed),
+            round: None,
+            cashed_out: false,
+        })
+    }
+
+    fn bet(&mut self, input: &str) -> Result<Vec<String>, String> {
+        if input.is_empty() {
+            self.cashed_out = true;
+            return Ok(Vec::new());
+        }
+        let bankroll = self.profile.bankroll;
+        let stake = match input.parse() {
+            Ok(stake) if (1..=bankroll).contains(&stake) => stake,
+            _ => {
+                return Err(i18n::text("wager.bad_stake", &[("bankroll", &bankroll)]));
+            }
+        };
+
+        // the stake is gone the moment the round starts
+        self.profile.bankroll -= stake;
+        self.profile.save()?;
+        let game =
+            Game::seeded(1u32, 100, 0, self.rng.next_u64()).with_max_attempts(Some(MAX_ATTEMPTS));
+        self.round = Some(Round {
+            stake,
+            replay: Replay::start("wager", &[], &game),
+            game,
+            start: Instant::now(),
+        });
+        Ok(Vec::new())
+    }
+
+    // Pays out or takes the stake once the round is over.
+    fn settle(&mut self, mut round: Round) -> Result<Vec<String>, String> {
+        let mut lines = round.replay.finish(&round.game);
+        let won = round.game.won();
+        let stake = round.stake;
+        if won {
+            let winnings = payout(stake, round.game.attempts(), self.settings.house_edge);
+            self.profile.bankroll += winnings;
+            let change = format!("{:+}", winnings as i64 - stake as i64);
+            lines.push(i18n::plural(
+                "wager.won",
+                round.game.attempts() as u64,
+                &[("winnings", &winnings), ("change", &change)],
+            ));
+        } else {
+            lines.push(i18n::text("wager.lost", &[("stake", &stake)]));
+        }
+
+        if self.profile.bankroll == 0 {
+            self.profile.bankruptcies += 1;
+            self.profile.bankroll = STARTING_BANKROLL;
+            lines.push(i18n::text(
+                "wager.bankrupt",
+                &[("bankroll", &STARTING_BANKROLL)],
+            ));
+        }
+        self.profile.save()?;
+        let millis = round.start.elapsed().as_millis() as u64;
+        lines.extend(history::record(
+            &self.profile.name,
+            GameRecord::from_game("wager", &round.game, won, millis),
+        ));
+        // recording may have unlocked achievements; don't overwrite them
+        self.profile = Profile::load(&self.profile.name)?;
+        Ok(lines)
+    }
+}
+
+impl GameMode for Wager {
+    fn name(&self) -> String {
+        String::from("wager")
+    }
+
+    fn intro(&self) -> Vec<String> {
+        let house_edge = self.settings.house_edge;
+        let first = payout(100, 1, house_edge) as f64 / 100.0;
+        let even = payout(100, BREAK_EVEN_ATTEMPTS as usize, house_edge) as f64 / 100.0;
+        vec![
+            i18n::text("wager.intro", &[]),
+            i18n::text(
+                "wager.odds",
+                &[("attempts", &MAX_ATTEMPTS), ("first", &first)],
+            ),
+            i18n::text(
+                "wager.edge",
+                &[
+                    ("attempt", &BREAK_EVEN_ATTEMPTS),
+                    ("even", &even),
+                    ("edge", &(house_edge * 100.0)),
+                ],
+            ),
+        ]
+    }
+
+    fn prompt(&self) -> String {
+        match self.round {
+            Some(_) => game::prompt(),
+            None => theme::paint(
+                Kind::Prompt,
+                &i18n::text("wager.prompt", &[("bankroll", &self.profile.bankroll)]),
+            ),
+        }
+    }
+
+    fn evaluate(&mut self, input: &str) -> Result<Vec<String>, String> {
+        let Some(round) = &mut self.round else {
+            return self.bet(input);
+        };
+        round.replay.inputs.push(Input {
+            at_ms: round.start.elapsed().as_millis() as u64,
+            line: input.to_string(),
+        });
+        let mut lines = game::answer(&mut round.game, input)?;
+        if round.game.is_finished()
+            && let Some(round) = self.round.take()
+        {
+            lines.extend(self.settle(round)?);
+        }
+        Ok(lines)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.cashed_out
+    }
+
+    fn finish(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(mut round) = self.round.take() {
+            lines.extend(game::give_up(&mut round.game));
+            match self.settle(round) {
+                Ok(settled) => lines.extend(settled),
+                Err(message) => lines.push(message),
+            }
+        }
+        lines.push(i18n::text(
+            "wager.leave",
+            &[("bankroll", &self.profile.bankroll)],
+        ));
+        lines
+    }
+
+    fn score(&self) -> Score {
+        Score {
+            won: false,
+            attempts: 0,
+            points: None,
+        }
+    }
+
+    // each round is recorded as it ends
+    fn record(&self, _: u64) -> Option<GameRecord> {
+        None
+    }
+}